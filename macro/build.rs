@@ -1,6 +1,8 @@
 use rustc_version::{version_meta, Channel};
 
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(use_unstable_features)");
+
     if matches!(version_meta().unwrap().channel, Channel::Nightly) {
         enable_unstable_features();
     }
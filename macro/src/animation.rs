@@ -0,0 +1,132 @@
+use crate::{args::Args, bitmap::Bitmap, emit::PixelData, options::Options, report};
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    AnimationDecoder, DynamicImage, Frame, ImageFormat, ImageResult,
+};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use std::{fs::File, io::BufReader, path::Path};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `animation!` macro.
+pub(crate) struct Animation {
+    frames: Vec<Bitmap>,
+    /// Display time of every frame, in milliseconds.
+    delays: Vec<u32>,
+}
+
+impl Parse for Animation {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let options = Options::from_args(&mut args, &lit_str)?;
+        args.finish()?;
+
+        let path = options.resolve(&lit_str)?;
+        let format = match ImageFormat::from_path(&path) {
+            Ok(format @ (ImageFormat::Gif | ImageFormat::Png)) => format,
+            _ => {
+                return Err(Error::new(
+                    lit_str.span(),
+                    format!(
+                        "unsupported animation format of {}, expected a GIF or an APNG file",
+                        path.display()
+                    ),
+                ))
+            }
+        };
+
+        let frames = decode(&path, format).map_err(|error| {
+            Error::new(
+                lit_str.span(),
+                format!("couldn't read {}: {}", path.display(), error),
+            )
+        })?;
+
+        if frames.is_empty() {
+            return Err(Error::new(
+                lit_str.span(),
+                format!("{} has no frames", path.display()),
+            ));
+        }
+
+        let delays = frames
+            .iter()
+            .map(|frame| {
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                (numer + denom / 2) / denom
+            })
+            .collect();
+
+        let frames = frames
+            .into_iter()
+            .enumerate()
+            .map(|(index, frame)| {
+                options
+                    .bitmap(frame.into_buffer(), lit_str.span())
+                    .map_err(|error| {
+                        Error::new(error.span(), format!("in frame {}: {}", index, error))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        report::record(
+            lit_str.span(),
+            Some(&path),
+            frames
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| (index.to_string(), frame)),
+            &mut PixelData::default(),
+        )?;
+
+        Ok(Self { frames, delays })
+    }
+}
+
+impl ToTokens for Animation {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut pixel_data = PixelData::default();
+        let stamps = self
+            .frames
+            .iter()
+            .map(|frame| pixel_data.push(frame))
+            .collect::<Vec<_>>();
+        let items = pixel_data.items();
+        let stamp_type = pixel_data.stamp_type();
+        let frame_count = self.frames.len();
+        let delays = &self.delays;
+
+        tokens.extend(quote! {
+            {
+                #items
+
+                static FRAMES: [#stamp_type; #frame_count] = unsafe { [#(#stamps),*] };
+                static DELAYS: [u32; #frame_count] = [#(#delays),*];
+
+                ::stockbook::Animation::new(&FRAMES, &DELAYS)
+            }
+        });
+    }
+}
+
+/// Decodes every frame of the animation at `path`, composited onto the full canvas.
+/// A PNG file that isn't animated is decoded as a single frame.
+fn decode(path: &Path, format: ImageFormat) -> ImageResult<Vec<Frame>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    match format {
+        ImageFormat::Gif => GifDecoder::new(reader)?.into_frames().collect_frames(),
+        _ => {
+            let decoder = PngDecoder::new(reader)?;
+            if decoder.is_apng() {
+                decoder.apng().into_frames().collect_frames()
+            } else {
+                let img = DynamicImage::from_decoder(decoder)?;
+                Ok(vec![Frame::new(img.to_rgba8())])
+            }
+        }
+    }
+}
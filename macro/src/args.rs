@@ -0,0 +1,264 @@
+use proc_macro2::Span;
+use std::{fmt::Display, str::FromStr};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    spanned::Spanned as _,
+    Expr, ExprLit, ExprRange, Ident, Lit, LitStr, RangeLimits, Token,
+};
+
+/// Optional arguments following the path in a macro invocation, e.g.
+/// `stamp!("image.png", foo = "bar")`.
+///
+/// Arguments are consumed one by one by the macro that parsed them. Whatever is
+/// left over at the end is reported by [`finish`](Args::finish) as unknown.
+#[derive(Default)]
+pub(crate) struct Args {
+    args: Vec<Arg>,
+}
+
+struct Arg {
+    name: Ident,
+    value: Option<Expr>,
+}
+
+/// Value of an argument, along with the span of the expression it was parsed from.
+pub(crate) struct Spanned<T> {
+    pub(crate) value: T,
+    pub(crate) span: Span,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = Vec::<Arg>::new();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let name = input.parse::<Ident>()?;
+            let value = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Some(input.parse::<Expr>()?)
+            } else {
+                None
+            };
+
+            if args.iter().any(|arg| arg.name == name) {
+                return Err(Error::new(
+                    name.span(),
+                    format!("duplicate argument `{}`", name),
+                ));
+            }
+
+            args.push(Arg { name, value });
+        }
+
+        Ok(Self { args })
+    }
+}
+
+impl Args {
+    /// Takes a `name` argument with no value.
+    pub(crate) fn flag(&mut self, name: &str) -> Result<bool> {
+        match self.take(name) {
+            Some(Arg { value: None, .. }) => Ok(true),
+            Some(Arg {
+                name,
+                value: Some(value),
+            }) => Err(Error::new(
+                value.span(),
+                format!("`{}` doesn't take a value", name),
+            )),
+            None => Ok(false),
+        }
+    }
+
+    /// Takes a `name = "..."` argument.
+    pub(crate) fn string(&mut self, name: &str) -> Result<Option<LitStr>> {
+        self.value(name)?
+            .map(|value| match value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => Ok(lit),
+                value => Err(Error::new(value.span(), "expected a string literal")),
+            })
+            .transpose()
+    }
+
+    /// Takes a `name = 'c'` argument.
+    pub(crate) fn char(&mut self, name: &str) -> Result<Option<Spanned<char>>> {
+        self.value(name)?
+            .map(|value| match value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Char(lit),
+                    ..
+                }) => Ok(Spanned {
+                    value: lit.value(),
+                    span: lit.span(),
+                }),
+                value => Err(Error::new(value.span(), "expected a character literal")),
+            })
+            .transpose()
+    }
+
+    /// Takes a `name = "abc"`, `name = 'a'..='z'`, or `name = ["abc", 'a'..='z', 'c']`
+    /// argument, and yields the characters in order.
+    pub(crate) fn chars(&mut self, name: &str) -> Result<Option<Spanned<Vec<char>>>> {
+        fn chars_of(expr: &Expr) -> Option<Vec<char>> {
+            match expr {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => Some(lit.value().chars().collect()),
+                Expr::Lit(ExprLit {
+                    lit: Lit::Char(lit),
+                    ..
+                }) => Some(vec![lit.value()]),
+                Expr::Range(ExprRange {
+                    from: Some(from),
+                    limits: RangeLimits::Closed(_),
+                    to: Some(to),
+                    ..
+                }) => match (&**from, &**to) {
+                    (
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Char(first),
+                            ..
+                        }),
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Char(last),
+                            ..
+                        }),
+                    ) => Some((first.value()..=last.value()).collect()),
+                    _ => None,
+                },
+                Expr::Array(array) => array.elems.iter().try_fold(Vec::new(), |mut chars, elem| {
+                    chars.extend(chars_of(elem)?);
+                    Some(chars)
+                }),
+                _ => None,
+            }
+        }
+
+        self.value(name)?
+            .map(|value| match chars_of(&value) {
+                Some(chars) => Ok(Spanned {
+                    value: chars,
+                    span: value.span(),
+                }),
+                None => Err(Error::new(
+                    value.span(),
+                    "expected a string literal, a range of characters like `'a'..='z'`, \
+                     or an array of those and character literals",
+                )),
+            })
+            .transpose()
+    }
+
+    /// Takes a `name = 123` argument.
+    pub(crate) fn int<N>(&mut self, name: &str) -> Result<Option<Spanned<N>>>
+    where
+        N: FromStr,
+        N::Err: Display,
+    {
+        self.value(name)?
+            .map(|value| match value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit), ..
+                }) => Ok(Spanned {
+                    value: lit.base10_parse()?,
+                    span: lit.span(),
+                }),
+                value => Err(Error::new(value.span(), "expected an integer literal")),
+            })
+            .transpose()
+    }
+
+    /// Takes a `name = (1, 2, 3)` argument with exactly `N` integers.
+    pub(crate) fn tuple<N, const LEN: usize>(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<Spanned<[N; LEN]>>>
+    where
+        N: FromStr,
+        N::Err: Display,
+    {
+        self.value(name)?
+            .map(|value| {
+                let error = || {
+                    let placeholders = vec!["_"; LEN].join(", ");
+                    Error::new(
+                        value.span(),
+                        format!("expected a tuple of {} integers, `({})`", LEN, placeholders),
+                    )
+                };
+
+                let Expr::Tuple(tuple) = &value else {
+                    return Err(error());
+                };
+                if tuple.elems.len() != LEN {
+                    return Err(error());
+                }
+
+                let elems = tuple
+                    .elems
+                    .iter()
+                    .map(|elem| match elem {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Int(lit), ..
+                        }) => lit.base10_parse(),
+                        elem => Err(Error::new(elem.span(), "expected an integer literal")),
+                    })
+                    .collect::<Result<Vec<N>>>()?;
+
+                Ok(Spanned {
+                    value: elems.try_into().map_err(|_| error())?,
+                    span: value.span(),
+                })
+            })
+            .transpose()
+    }
+
+    fn value(&mut self, name: &str) -> Result<Option<Expr>> {
+        match self.take(name) {
+            Some(Arg {
+                value: Some(value), ..
+            }) => Ok(Some(value)),
+            Some(Arg { name, value: None }) => Err(Error::new(
+                name.span(),
+                format!("`{}` requires a value, e.g. `{} = ...`", name, name),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Checks if there's a `name` argument, without taking it.
+    pub(crate) fn has(&self, name: &str) -> bool {
+        self.args.iter().any(|arg| arg.name == name)
+    }
+
+    /// Span of the name of the `name` argument, if there is one, without taking it.
+    pub(crate) fn span(&self, name: &str) -> Option<Span> {
+        self.args
+            .iter()
+            .find(|arg| arg.name == name)
+            .map(|arg| arg.name.span())
+    }
+
+    fn take(&mut self, name: &str) -> Option<Arg> {
+        let index = self.args.iter().position(|arg| arg.name == name)?;
+        Some(self.args.remove(index))
+    }
+
+    /// Fails on the first argument that has not been taken.
+    pub(crate) fn finish(self) -> Result<()> {
+        match self.args.into_iter().next() {
+            Some(arg) => Err(Error::new(
+                arg.name.span(),
+                format!("unknown argument `{}`", arg.name),
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{
+    args::Args,
+    bitmap::Bitmap,
+    font::{Font, FontArgs, Glyph, Metrics},
+    path::{self, RelativeTo},
+    track_file_if_available,
+};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::{fs, str::SplitWhitespace};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `bdf_font!` macro.
+pub(crate) struct BdfFont {
+    font: Font,
+}
+
+/// Parts of a BDF file needed to render its glyphs.
+struct Bdf {
+    ascent: isize,
+    descent: isize,
+    glyphs: Vec<BdfGlyph>,
+}
+
+struct BdfGlyph {
+    char: char,
+    /// Horizontal component of `DWIDTH`.
+    advance: isize,
+    /// Width, height, and the offset of the bottom-left corner from the origin.
+    bbx: [isize; 4],
+    /// Rows of the bitmap, top to bottom, each padded to a whole number of bytes.
+    rows: Vec<Vec<u8>>,
+}
+
+/// Arguments of the `bdf_font!` macro, except for the path of the font.
+pub(crate) struct BdfArgs {
+    font_args: FontArgs,
+    relative_to: Option<RelativeTo>,
+}
+
+impl Parse for BdfFont {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let bdf_args = BdfArgs::from_args(&mut args)?;
+        args.finish()?;
+
+        let font = bdf_args.load(&lit_str)?;
+        font.record(lit_str.span())?;
+
+        Ok(Self { font })
+    }
+}
+
+impl BdfArgs {
+    pub(crate) fn from_args(args: &mut Args) -> Result<Self> {
+        let font_args = FontArgs::from_args(args)?;
+        let relative_to = args
+            .string("relative_to")?
+            .map(|lit| RelativeTo::from_lit(&lit))
+            .transpose()?;
+
+        Ok(Self {
+            font_args,
+            relative_to,
+        })
+    }
+
+    /// Reads and parses the font at the path written in `lit_str`.
+    pub(crate) fn load(self, lit_str: &LitStr) -> Result<Font> {
+        let Self {
+            font_args,
+            relative_to,
+        } = self;
+
+        let path = path::resolve(lit_str, relative_to)?;
+        track_file_if_available(&path);
+
+        let source = fs::read_to_string(&path).map_err(|error| {
+            Error::new(
+                lit_str.span(),
+                format!("couldn't read {}: {}", path.display(), error),
+            )
+        })?;
+        let bdf = parse_bdf(&source).map_err(|error| {
+            Error::new(
+                lit_str.span(),
+                format!("couldn't parse {}: {}", path.display(), error),
+            )
+        })?;
+
+        let available = bdf
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.char)
+            .collect::<Vec<_>>();
+        let subset = font_args.subset(&available, available.clone())?;
+
+        let glyphs = bdf
+            .glyphs
+            .iter()
+            .filter(|glyph| subset.binary_search(&glyph.char).is_ok())
+            .map(|glyph| {
+                let [width, height, offset_x, offset_y] = glyph.bbx;
+                let (width, height) = (width as usize, height as usize);
+                let bits = (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .map(|(x, y)| glyph.rows[y][x / 8] & (0b1000_0000 >> (x % 8)) != 0)
+                    .collect::<Vec<_>>();
+
+                Glyph {
+                    char: glyph.char,
+                    bitmap: Bitmap {
+                        width,
+                        height,
+                        data: Bitmap::encode(bits.into_iter()),
+                        mask: None,
+                    },
+                    offset: [offset_x, bdf.ascent - (height as isize + offset_y)],
+                    advance: glyph.advance.max(0) as usize + font_args.letter_spacing,
+                }
+            })
+            .collect();
+
+        let metrics = Metrics {
+            ascent: bdf.ascent.max(0) as usize,
+            descent: bdf.descent.max(0) as usize,
+            line_height: (bdf.ascent + bdf.descent).max(0) as usize + font_args.line_spacing,
+        };
+        let span = font_args
+            .chars
+            .as_ref()
+            .map_or(lit_str.span(), |chars| chars.span);
+        Font::new(glyphs, metrics, font_args.fallback, path, span)
+    }
+}
+
+impl ToTokens for BdfFont {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.font.to_tokens(tokens);
+    }
+}
+
+/// Parses the source of a BDF file. Glyphs without an encoding, or with an encoding
+/// that isn't a valid character, are skipped.
+fn parse_bdf(source: &str) -> std::result::Result<Bdf, String> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()));
+
+    let mut bounding_box = None;
+    let mut ascent = None;
+    let mut descent = None;
+    let mut default_advance = None;
+    let mut glyphs = Vec::new();
+
+    let mut in_char = false;
+    let mut encoding = None;
+    let mut advance = None;
+    let mut bbx = None;
+    let mut rows = Vec::new();
+
+    while let Some((number, line)) = lines.next() {
+        let mut words = line.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+        let error = |message: &str| format!("line {}: {}", number, message);
+        let mut expect_integers = |count: usize| {
+            integers(&mut words, count)
+                .ok_or_else(|| error(&format!("expected {} integers after {}", count, keyword)))
+        };
+
+        match keyword {
+            "FONTBOUNDINGBOX" => bounding_box = Some(expect_integers(4)?),
+            "FONT_ASCENT" => ascent = Some(expect_integers(1)?[0]),
+            "FONT_DESCENT" => descent = Some(expect_integers(1)?[0]),
+            // A font-wide default, when it's outside of a glyph
+            "DWIDTH" if !in_char => default_advance = Some(expect_integers(1)?[0]),
+            "DWIDTH" => advance = Some(expect_integers(1)?[0]),
+            "STARTCHAR" => {
+                in_char = true;
+                encoding = None;
+                advance = default_advance;
+                bbx = None;
+                rows = Vec::new();
+            }
+            "ENCODING" => encoding = Some(expect_integers(1)?[0]),
+            "BBX" => bbx = Some(expect_integers(4)?),
+            "BITMAP" => {
+                let Some(&[width, height, ..]) = bbx.as_deref() else {
+                    return Err(error("BITMAP before BBX"));
+                };
+                let row_len = (width.max(0) as usize).div_ceil(8);
+
+                for _ in 0..height.max(0) {
+                    let (number, line) = lines
+                        .next()
+                        .ok_or_else(|| error("unexpected end of the bitmap"))?;
+                    let row = parse_hex_row(line, row_len)
+                        .ok_or_else(|| format!("line {}: invalid bitmap row", number))?;
+                    rows.push(row);
+                }
+            }
+            "ENDCHAR" => {
+                in_char = false;
+                let Some(bbx) = bbx.take() else {
+                    return Err(error("missing BBX"));
+                };
+                if rows.len() != bbx[1].max(0) as usize {
+                    return Err(error("missing BITMAP"));
+                }
+                let char = encoding
+                    .and_then(|encoding| u32::try_from(encoding).ok())
+                    .and_then(char::from_u32);
+
+                if let Some(char) = char {
+                    glyphs.push(BdfGlyph {
+                        char,
+                        advance: advance.unwrap_or(bbx[0]),
+                        bbx: [bbx[0].max(0), bbx[1].max(0), bbx[2], bbx[3]],
+                        rows: std::mem::take(&mut rows),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let bounding_box = bounding_box.ok_or("missing FONTBOUNDINGBOX")?;
+
+    Ok(Bdf {
+        ascent: ascent.unwrap_or(bounding_box[1] + bounding_box[3]),
+        descent: descent.unwrap_or(-bounding_box[3]),
+        glyphs,
+    })
+}
+
+fn integers(words: &mut SplitWhitespace, count: usize) -> Option<Vec<isize>> {
+    (0..count).map(|_| words.next()?.parse().ok()).collect()
+}
+
+/// Parses a row of hexadecimal digits with at least `len` bytes.
+fn parse_hex_row(line: &str, len: usize) -> Option<Vec<u8>> {
+    if !line.is_ascii() || line.len() < 2 * len || !line.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..line.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&line[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,64 @@
+/// Encoded image &mdash; the compile-time counterpart of a `Stamp` or a
+/// `MaskedStamp`.
+pub(crate) struct Bitmap {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) data: Vec<u8>,
+    pub(crate) mask: Option<Vec<u8>>,
+}
+
+impl Bitmap {
+    /// Packs bits into bytes, most significant bit first.
+    pub(crate) fn encode(bits: impl ExactSizeIterator<Item = bool>) -> Vec<u8> {
+        let mut data = vec![0u8; encoding_len(bits.len())];
+
+        for (index, bit) in bits.enumerate() {
+            let byte_index = index / 8;
+            let bit_index = 7 - (index % 8);
+
+            if bit {
+                data[byte_index] |= 1 << bit_index;
+            }
+        }
+
+        data
+    }
+
+    /// Whether the pixel at _(x, y)_ is white.
+    pub(crate) fn is_white(&self, x: usize, y: usize) -> bool {
+        bit(&self.data, y * self.width + x)
+    }
+
+    /// Copies the `width`x`height` part of the bitmap whose top-left corner is at
+    /// _(x, y)_. The part must be within the bounds of the bitmap.
+    pub(crate) fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let crop_plane = |plane: &[u8]| {
+            let indices = (y..y + height)
+                .flat_map(|y| (x..x + width).map(move |x| y * self.width + x))
+                .collect::<Vec<_>>();
+            Self::encode(indices.into_iter().map(|index| bit(plane, index)))
+        };
+
+        Self {
+            width,
+            height,
+            data: crop_plane(&self.data),
+            mask: self.mask.as_deref().map(crop_plane),
+        }
+    }
+}
+
+fn bit(plane: &[u8], index: usize) -> bool {
+    plane[index / 8] & (0b1000_0000 >> (index % 8)) != 0
+}
+
+fn encoding_len(pixel_count: usize) -> usize {
+    let d = pixel_count / 8;
+    let r = pixel_count % 8;
+
+    if r > 0 {
+        d + 1
+    } else {
+        d
+    }
+}
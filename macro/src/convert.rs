@@ -0,0 +1,430 @@
+use crate::{args::Args, dither::Dither, Color};
+use image::{Pixel as _, Rgba, RgbaImage};
+use proc_macro2::Span;
+use std::{collections::HashMap, fmt::Write as _};
+use syn::{
+    parse::{Error, Result},
+    LitStr,
+};
+
+/// Way of turning the pixels of an image into [`Color`]s.
+pub(crate) struct Conversion {
+    method: Method,
+    invert: bool,
+}
+
+enum Method {
+    /// Only pixels of exactly one of the two colors are accepted.
+    Palette { black: Rgba<u8>, white: Rgba<u8> },
+    /// Pixels whose `channel` value is at least `level` become white, the rest
+    /// become black, optionally dithering the values beforehand.
+    Threshold {
+        level: u8,
+        channel: Channel,
+        dither: Option<Dither>,
+    },
+}
+
+/// Source of a pixel's value for the [`Threshold`](Method::Threshold) conversion.
+#[derive(Clone, Copy)]
+pub(crate) enum Channel {
+    Luma,
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl Conversion {
+    /// Parses the conversion arguments. If the image is `resampled`, it's thresholded
+    /// by default. If it's `masked`, its opaque pixels lose their alpha before they're
+    /// converted, so the arguments that depend on the alpha are rejected.
+    pub(crate) fn from_args(args: &mut Args, resampled: bool, masked: bool) -> Result<Self> {
+        let threshold = args.int::<u8>("threshold")?.map(|level| level.value);
+        let channel = args
+            .string("channel")?
+            .map(|lit| Channel::from_lit(&lit).map(|channel| (channel, lit)))
+            .transpose()?;
+        let dither = args
+            .string("dither")?
+            .map(|lit| Dither::from_lit(&lit))
+            .transpose()?;
+        let black = args
+            .string("black")?
+            .map(|lit| parse_color(&lit).map(|color| (color, lit)))
+            .transpose()?;
+        let white = args
+            .string("white")?
+            .map(|lit| parse_color(&lit).map(|color| (color, lit)))
+            .transpose()?;
+        let invert = args.flag("invert")?;
+
+        if masked {
+            if let Some((Channel::Alpha, lit)) = &channel {
+                return Err(Error::new(
+                    lit.span(),
+                    "`channel = \"alpha\"` can't be used together with `transparent`, since \
+                     the alpha of opaque pixels is ignored",
+                ));
+            }
+            if let Some((_, lit)) = [&black, &white]
+                .into_iter()
+                .flatten()
+                .find(|(color, _)| color.0[3] != 255)
+            {
+                return Err(Error::new(
+                    lit.span(),
+                    "`black` and `white` can't have an alpha together with `transparent`, \
+                     since the alpha of opaque pixels is ignored",
+                ));
+            }
+        }
+
+        let method = match (threshold, channel, dither) {
+            (None, Some((_, lit)), None) if !resampled => {
+                return Err(Error::new(
+                    lit.span(),
+                    "`channel` can only be used together with `threshold`, `dither` or `size`",
+                ))
+            }
+            (None, None, None) if !resampled => Method::Palette {
+                black: black.map_or(Rgba([0, 0, 0, 255]), |(color, _)| color),
+                white: white.map_or(Rgba([255, 255, 255, 255]), |(color, _)| color),
+            },
+            (level, channel, dither) => {
+                if let Some((_, lit)) = black.or(white) {
+                    return Err(Error::new(
+                        lit.span(),
+                        "`black` and `white` can't be used together with `threshold`, \
+                         `dither` or `size`",
+                    ));
+                }
+
+                Method::Threshold {
+                    level: level.unwrap_or(128),
+                    channel: channel.map_or(Channel::Luma, |(channel, _)| channel),
+                    dither,
+                }
+            }
+        };
+
+        Ok(Self { method, invert })
+    }
+
+    /// Color of the image that becomes [`Color::Black`], or any color that does if
+    /// there's more than one.
+    pub(crate) fn black(&self) -> Rgba<u8> {
+        match self.method {
+            Method::Palette { black, .. } => black,
+            Method::Threshold { .. } => Rgba([0, 0, 0, 255]),
+        }
+    }
+
+    /// Converts every pixel of `img`, in row-major order.
+    pub(crate) fn apply(&self, img: &RgbaImage, span: Span) -> Result<Vec<Color>> {
+        let colors = match &self.method {
+            Method::Palette { black, white } => {
+                let mut invalid = HashMap::<Rgba<u8>, Occurrences>::new();
+                let colors = img
+                    .enumerate_pixels()
+                    .map(|(x, y, pixel)| {
+                        if pixel == black {
+                            Color::Black
+                        } else if pixel == white {
+                            Color::White
+                        } else {
+                            let occurrences = invalid.entry(*pixel).or_default();
+                            occurrences.count += 1;
+                            if occurrences.examples.len() < EXAMPLES {
+                                occurrences.examples.push((x, y));
+                            }
+                            Color::Black
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                if !invalid.is_empty() {
+                    let used = |color| img.pixels().any(|pixel| pixel == color);
+                    let valid = [black, white].into_iter().filter(|&color| used(color));
+                    return Err(Error::new(
+                        span,
+                        summarize(invalid, valid.copied().collect(), black, white),
+                    ));
+                }
+
+                colors
+            }
+            Method::Threshold {
+                level,
+                channel,
+                dither: None,
+            } => img
+                .pixels()
+                .map(|pixel| {
+                    if channel.value(pixel) >= *level {
+                        Color::White
+                    } else {
+                        Color::Black
+                    }
+                })
+                .collect(),
+            Method::Threshold {
+                level,
+                channel,
+                dither: Some(dither),
+            } => {
+                let values = img.pixels().map(|pixel| channel.value(pixel)).collect();
+                dither.apply(values, img.width() as usize, *level)
+            }
+        };
+
+        if self.invert {
+            Ok(colors.into_iter().map(Color::invert).collect())
+        } else {
+            Ok(colors)
+        }
+    }
+}
+
+impl Channel {
+    fn from_lit(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "luma" => Ok(Self::Luma),
+            "red" => Ok(Self::Red),
+            "green" => Ok(Self::Green),
+            "blue" => Ok(Self::Blue),
+            "alpha" => Ok(Self::Alpha),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "invalid `channel` value \"{}\", expected one of \"luma\", \"red\", \
+                     \"green\", \"blue\" or \"alpha\"",
+                    other
+                ),
+            )),
+        }
+    }
+
+    fn value(self, pixel: &Rgba<u8>) -> u8 {
+        match self {
+            Self::Luma => pixel.to_luma().0[0],
+            Self::Red => pixel.0[0],
+            Self::Green => pixel.0[1],
+            Self::Blue => pixel.0[2],
+            Self::Alpha => pixel.0[3],
+        }
+    }
+}
+
+/// Pixels of a single color that can't be converted.
+#[derive(Default)]
+struct Occurrences {
+    count: usize,
+    /// Coordinates of the first few of them.
+    examples: Vec<(u32, u32)>,
+}
+
+/// Number of invalid colors, and of pixels of every such color, listed in the
+/// summary of invalid pixels.
+const EXAMPLES: usize = 3;
+
+/// Describes the pixels of an image that are neither `black` nor `white`, given the
+/// occurrences of every `invalid` color, and the `valid` colors that the image uses
+/// too. Suggests the arguments that would likely convert the image instead.
+fn summarize(
+    invalid: HashMap<Rgba<u8>, Occurrences>,
+    valid: Vec<Rgba<u8>>,
+    black: &Rgba<u8>,
+    white: &Rgba<u8>,
+) -> String {
+    let mut invalid = invalid.into_iter().collect::<Vec<_>>();
+    invalid.sort_by(|(a, a_occurrences), (b, b_occurrences)| {
+        b_occurrences
+            .count
+            .cmp(&a_occurrences.count)
+            .then(a.0.cmp(&b.0))
+    });
+    let total = invalid
+        .iter()
+        .map(|(_, occurrences)| occurrences.count)
+        .sum::<usize>();
+
+    let (pixels, are) = if total == 1 {
+        ("pixel", "is")
+    } else {
+        ("pixels", "are")
+    };
+    let mut summary = format!(
+        "found {} {} that {} neither black ({}) nor white ({})",
+        total,
+        pixels,
+        are,
+        hex(black),
+        hex(white),
+    );
+
+    for (color, Occurrences { count, examples }) in invalid.iter().take(EXAMPLES) {
+        let examples = examples
+            .iter()
+            .map(|(x, y)| format!("({}, {})", x, y))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ellipsis = if *count > EXAMPLES { ", ..." } else { "" };
+        write!(
+            summary,
+            "\n  {} \u{d7} {}, at {}{}",
+            count,
+            hex(color),
+            examples,
+            ellipsis
+        )
+        .unwrap();
+    }
+
+    if invalid.len() > EXAMPLES {
+        let others = invalid.len() - EXAMPLES;
+        let colors = if others == 1 { "color" } else { "colors" };
+        write!(summary, "\n  and {} other {}", others, colors).unwrap();
+    }
+
+    let all_colors = invalid
+        .iter()
+        .map(|(color, _)| *color)
+        .chain(valid)
+        .collect::<Vec<_>>();
+    let luma = |color: &Rgba<u8>| color.to_luma().0[0];
+
+    if invalid.iter().any(|(color, _)| color.0[3] < 255) {
+        summary += "\nhint: some of them are translucent, consider `transparent = \"alpha\"`";
+    } else if let [a, b] = all_colors[..] {
+        let (dark, light) = if luma(&a) <= luma(&b) { (a, b) } else { (b, a) };
+        write!(
+            summary,
+            "\nhint: the image has exactly two colors, consider `black = \"{}\", white = \"{}\"`",
+            hex(&dark),
+            hex(&light),
+        )
+        .unwrap();
+    } else if invalid.len() > 16 {
+        // Anti-aliased edges rarely blend into more than a handful of shades
+        summary +=
+            "\nhint: looks like a photo or a gradient, consider `dither = \"floyd-steinberg\"`";
+    } else {
+        summary += "\nhint: looks anti-aliased, consider `threshold = 128`";
+    }
+
+    summary
+}
+
+fn hex(color: &Rgba<u8>) -> String {
+    let [r, g, b, a] = color.0;
+    format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+/// Parses a `"#rrggbb"` or `"#rrggbbaa"` color. The alpha of a color without one is
+/// 255.
+pub(crate) fn parse_color(lit: &LitStr) -> Result<Rgba<u8>> {
+    let value = lit.value();
+    let digits = value.strip_prefix('#').unwrap_or_default();
+
+    let channels = if digits.is_ascii() && matches!(digits.len(), 6 | 8) {
+        (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+            .collect::<Option<Vec<_>>>()
+    } else {
+        None
+    };
+
+    match channels.as_deref() {
+        Some(&[r, g, b]) => Ok(Rgba([r, g, b, 255])),
+        Some(&[r, g, b, a]) => Ok(Rgba([r, g, b, a])),
+        _ => Err(Error::new(
+            lit.span(),
+            format!(
+                "invalid color \"{}\", expected \"#rrggbb\" or \"#rrggbbaa\"",
+                value
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn occurrences(count: usize, examples: &[(u32, u32)]) -> Occurrences {
+        Occurrences {
+            count,
+            examples: examples.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_summary_lists_most_common_colors() {
+        let invalid = HashMap::from([
+            (Rgba([32, 32, 32, 255]), occurrences(1, &[(5, 5)])),
+            (
+                Rgba([192, 192, 192, 255]),
+                occurrences(2, &[(3, 3), (4, 4)]),
+            ),
+            (
+                Rgba([64, 64, 64, 255]),
+                occurrences(5, &[(0, 0), (1, 0), (2, 0)]),
+            ),
+            (
+                Rgba([128, 128, 128, 255]),
+                occurrences(2, &[(0, 1), (1, 1)]),
+            ),
+        ]);
+
+        assert_eq!(
+            summarize(invalid, vec![BLACK, WHITE], &BLACK, &WHITE),
+            "found 10 pixels that are neither black (#000000ff) nor white (#ffffffff)\n  \
+             5 \u{d7} #404040ff, at (0, 0), (1, 0), (2, 0), ...\n  \
+             2 \u{d7} #808080ff, at (0, 1), (1, 1)\n  \
+             2 \u{d7} #c0c0c0ff, at (3, 3), (4, 4)\n  \
+             and 1 other color\n\
+             hint: looks anti-aliased, consider `threshold = 128`"
+        );
+    }
+
+    #[test]
+    fn test_summary_suggests_alpha_for_translucent_pixels() {
+        let invalid = HashMap::from([(Rgba([0, 0, 0, 128]), occurrences(1, &[(2, 3)]))]);
+
+        assert_eq!(
+            summarize(invalid, vec![BLACK], &BLACK, &WHITE),
+            "found 1 pixel that is neither black (#000000ff) nor white (#ffffffff)\n  \
+             1 \u{d7} #00000080, at (2, 3)\n\
+             hint: some of them are translucent, consider `transparent = \"alpha\"`"
+        );
+    }
+
+    #[test]
+    fn test_summary_suggests_mapping_two_colors() {
+        let invalid = HashMap::from([(Rgba([255, 0, 0, 255]), occurrences(4, &[(0, 0)]))]);
+        let summary = summarize(invalid, vec![Rgba([0, 0, 128, 255])], &BLACK, &WHITE);
+
+        assert!(summary.ends_with(
+            "\nhint: the image has exactly two colors, \
+             consider `black = \"#000080ff\", white = \"#ff0000ff\"`"
+        ));
+    }
+
+    #[test]
+    fn test_summary_suggests_dithering_many_colors() {
+        let invalid = (1..=17)
+            .map(|shade| (Rgba([shade, shade, shade, 255]), occurrences(1, &[(0, 0)])))
+            .collect();
+        let summary = summarize(invalid, vec![BLACK, WHITE], &BLACK, &WHITE);
+
+        assert!(summary.contains("\n  and 14 other colors\n"));
+        assert!(summary.ends_with(
+            "\nhint: looks like a photo or a gradient, consider `dither = \"floyd-steinberg\"`"
+        ));
+    }
+}
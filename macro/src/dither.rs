@@ -0,0 +1,128 @@
+use crate::Color;
+use syn::{
+    parse::{Error, Result},
+    LitStr,
+};
+
+/// Dithering algorithm applied before thresholding.
+#[derive(Clone, Copy)]
+pub(crate) enum Dither {
+    /// Error diffusion with the Floyd–Steinberg kernel.
+    FloydSteinberg,
+    /// Error diffusion with the Atkinson kernel, which only propagates 3/4 of the
+    /// error, trading detail in shadows and highlights for contrast.
+    Atkinson,
+    /// Ordered dithering with a Bayer matrix of the given size.
+    Bayer(usize),
+}
+
+/// Neighbour offset and the fraction of the quantization error it receives.
+type Kernel = &'static [(isize, usize, f32)];
+
+const FLOYD_STEINBERG: Kernel = &[
+    (1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    (0, 1, 5.0 / 16.0),
+    (1, 1, 1.0 / 16.0),
+];
+
+const ATKINSON: Kernel = &[
+    (1, 0, 1.0 / 8.0),
+    (2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    (0, 1, 1.0 / 8.0),
+    (1, 1, 1.0 / 8.0),
+    (0, 2, 1.0 / 8.0),
+];
+
+impl Dither {
+    pub(crate) fn from_lit(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "floyd-steinberg" => Ok(Self::FloydSteinberg),
+            "atkinson" => Ok(Self::Atkinson),
+            "bayer2x2" => Ok(Self::Bayer(2)),
+            "bayer4x4" => Ok(Self::Bayer(4)),
+            "bayer8x8" => Ok(Self::Bayer(8)),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "invalid `dither` value \"{}\", expected one of \"floyd-steinberg\", \
+                     \"atkinson\", \"bayer2x2\", \"bayer4x4\" or \"bayer8x8\"",
+                    other
+                ),
+            )),
+        }
+    }
+
+    /// Dithers row-major `values` of an image `width` pixels wide. `level` is the
+    /// value at which a pixel turns from black to white.
+    pub(crate) fn apply(self, values: Vec<u8>, width: usize, level: u8) -> Vec<Color> {
+        match self {
+            Self::FloydSteinberg => diffuse(values, width, level, FLOYD_STEINBERG),
+            Self::Atkinson => diffuse(values, width, level, ATKINSON),
+            Self::Bayer(size) => ordered(values, width, level, size),
+        }
+    }
+}
+
+fn diffuse(values: Vec<u8>, width: usize, level: u8, kernel: Kernel) -> Vec<Color> {
+    let height = values.len().checked_div(width).unwrap_or(0);
+    let mut values = values.into_iter().map(f32::from).collect::<Vec<_>>();
+    let mut colors = Vec::with_capacity(values.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let value = values[y * width + x];
+            let (color, quantized) = if value >= f32::from(level) {
+                (Color::White, 255.0)
+            } else {
+                (Color::Black, 0.0)
+            };
+            let error = value - quantized;
+
+            for &(dx, dy, weight) in kernel {
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx >= 0 && (nx as usize) < width && ny < height {
+                    values[ny * width + nx as usize] += error * weight;
+                }
+            }
+
+            colors.push(color);
+        }
+    }
+
+    colors
+}
+
+fn ordered(values: Vec<u8>, width: usize, level: u8, size: usize) -> Vec<Color> {
+    let bias = 128.0 - f32::from(level);
+    let scale = 256.0 / (size * size) as f32;
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let (x, y) = (index % width, index / width);
+            let threshold = (bayer(x % size, y % size, size) as f32 + 0.5) * scale;
+
+            if f32::from(value) + bias >= threshold {
+                Color::White
+            } else {
+                Color::Black
+            }
+        })
+        .collect()
+}
+
+/// Entry of the `size`x`size` Bayer matrix, where `size` is a power of two.
+fn bayer(x: usize, y: usize, size: usize) -> usize {
+    if size == 1 {
+        return 0;
+    }
+
+    let half = size / 2;
+    let quadrant = [[0, 2], [3, 1]][y / half][x / half];
+
+    4 * bayer(x % half, y % half, half) + quadrant
+}
@@ -0,0 +1,248 @@
+use crate::bitmap::Bitmap;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::ops::Range;
+use syn::parse::Error;
+
+/// Pixel data of one or more bitmaps, laid out one after another in a single static
+/// array per bitplane.
+///
+/// Bitmaps with the same pixels as one pushed before, e.g. repeated tiles of a sprite
+/// sheet, point at the pixel data of that one instead of being laid out again.
+#[derive(Default)]
+pub(crate) struct PixelData {
+    data: Vec<u8>,
+    mask: Option<Vec<u8>>,
+    /// Ranges of the arrays taken by each of the distinct bitmaps.
+    ranges: Vec<Range<usize>>,
+}
+
+impl PixelData {
+    /// Appends the bitplanes of `bitmap` and returns an expression that constructs a
+    /// `Stamp`, or a `MaskedStamp`, pointing at them. The expression must be placed
+    /// in an `unsafe` context that is in the scope of [`items`](PixelData::items).
+    ///
+    /// Either all or none of the pushed bitmaps must have a mask.
+    pub(crate) fn push(&mut self, bitmap: &Bitmap) -> TokenStream {
+        let offset = self.append(bitmap);
+        let width = bitmap.width;
+        let height = bitmap.height;
+        let data_ptr = pointer(quote!(PIXEL_DATA), offset);
+
+        match &bitmap.mask {
+            None => quote! {
+                ::stockbook::Stamp::from_raw(#width, #height, #data_ptr)
+            },
+            Some(_) => {
+                let mask_ptr = pointer(quote!(MASK_DATA), offset);
+
+                quote! {
+                    ::stockbook::MaskedStamp::from_raw(#width, #height, #data_ptr, #mask_ptr)
+                }
+            }
+        }
+    }
+
+    /// Appends the bitplane of `bitmap`, which must not have a mask, and returns an
+    /// expression that constructs a `SizedStamp` pointing at it, like
+    /// [`push`](PixelData::push) does.
+    pub(crate) fn push_sized(&mut self, bitmap: &Bitmap) -> TokenStream {
+        let offset = self.append(bitmap);
+        let width = bitmap.width;
+        let height = bitmap.height;
+        let data_ptr = pointer(quote!(PIXEL_DATA), offset);
+
+        quote! {
+            ::stockbook::SizedStamp::<#width, #height>::from_raw(#data_ptr)
+        }
+    }
+
+    /// Type of the expressions returned by [`push`](PixelData::push).
+    pub(crate) fn stamp_type(&self) -> TokenStream {
+        match self.mask {
+            None => quote!(::stockbook::Stamp),
+            Some(_) => quote!(::stockbook::MaskedStamp),
+        }
+    }
+
+    /// Static items holding the pixel data.
+    pub(crate) fn items(&self) -> TokenStream {
+        #[cfg(feature = "progmem")]
+        let progmem_attr = quote! {
+            #[cfg_attr(target_arch = "avr", link_section = ".progmem.data")]
+        };
+        #[cfg(not(feature = "progmem"))]
+        let progmem_attr = TokenStream::new();
+
+        let array_len = self.data.len();
+        let array = byte_array(&self.data);
+
+        let mask_item = self.mask.as_ref().map(|mask| {
+            let mask_array = byte_array(mask);
+
+            quote! {
+                #progmem_attr
+                static MASK_DATA: [u8; #array_len] = #mask_array;
+            }
+        });
+
+        quote! {
+            #progmem_attr
+            static PIXEL_DATA: [u8; #array_len] = #array;
+            #mask_item
+        }
+    }
+
+    /// Number of bytes of all of the bitplanes.
+    pub(crate) fn len(&self) -> usize {
+        self.data.len() + self.mask.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Appends the bitplanes of `bitmap`, unless a bitmap with the same bitplanes has
+    /// been appended already, and returns their offset.
+    pub(crate) fn append(&mut self, bitmap: &Bitmap) -> usize {
+        let existing = self.ranges.iter().find(|range| {
+            self.data[(*range).clone()] == bitmap.data[..]
+                && match (&self.mask, &bitmap.mask) {
+                    (Some(mask), Some(other)) => mask[(*range).clone()] == other[..],
+                    (None, None) => true,
+                    _ => false,
+                }
+        });
+        if let Some(range) = existing {
+            return range.start;
+        }
+
+        let offset = self.data.len();
+        self.data.extend_from_slice(&bitmap.data);
+        if let Some(mask) = &bitmap.mask {
+            self.mask
+                .get_or_insert_with(Vec::new)
+                .extend_from_slice(mask);
+        }
+        self.ranges.push(offset..self.data.len());
+
+        offset
+    }
+}
+
+/// Type of the stamp yielded by a macro.
+#[derive(Clone, Copy)]
+pub(crate) enum StampKind {
+    Plain,
+    Masked,
+    Sized,
+}
+
+/// Expression that reports `error` at compile time, and otherwise evaluates to an
+/// empty stamp of the given `kind`, so that the code using the stamp that couldn't be
+/// included is still type-checked. The dimensions of a `SizedStamp` are inferred.
+pub(crate) fn placeholder(error: &Error, kind: StampKind) -> TokenStream {
+    let error = error.to_compile_error();
+    let stamp = match kind {
+        StampKind::Plain => quote!(::stockbook::Stamp::from_raw(0, 0, [].as_ptr())),
+        StampKind::Masked => quote!(::stockbook::MaskedStamp::from_raw(
+            0,
+            0,
+            [].as_ptr(),
+            [].as_ptr()
+        )),
+        StampKind::Sized => quote!(::stockbook::SizedStamp::from_raw([].as_ptr())),
+    };
+
+    quote! {
+        {
+            #error
+
+            unsafe { #stamp }
+        }
+    }
+}
+
+fn pointer(array: TokenStream, offset: usize) -> TokenStream {
+    if offset == 0 {
+        quote!(#array.as_ptr())
+    } else {
+        quote!(#array.as_ptr().add(#offset))
+    }
+}
+
+fn byte_array(data: &[u8]) -> syn::ExprArray {
+    syn::ExprArray {
+        attrs: Default::default(),
+        bracket_token: Default::default(),
+        elems: data
+            .iter()
+            .map(|byte| {
+                syn::Expr::Lit(syn::ExprLit {
+                    attrs: Default::default(),
+                    lit: syn::Lit::Int(syn::LitInt::new(&byte.to_string(), Span::call_site())),
+                })
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error() -> Error {
+        Error::new(Span::call_site(), "image not found")
+    }
+
+    fn bitmap(data: Vec<u8>) -> Bitmap {
+        Bitmap {
+            width: 8,
+            height: 1,
+            data,
+            mask: None,
+        }
+    }
+
+    #[test]
+    fn test_append_identical_bitmap() {
+        let mut pixel_data = PixelData::default();
+
+        assert_eq!(pixel_data.append(&bitmap(vec![0b1010_1010])), 0);
+        assert_eq!(pixel_data.append(&bitmap(vec![0b0101_0101])), 1);
+        assert_eq!(pixel_data.append(&bitmap(vec![0b1010_1010])), 0);
+        assert_eq!(pixel_data.len(), 2);
+    }
+
+    #[test]
+    fn test_placeholder_reports_error() {
+        let tokens = placeholder(&error(), StampKind::Plain).to_string();
+
+        assert_eq!(
+            tokens,
+            quote! {
+                {
+                    compile_error! { "image not found" }
+
+                    unsafe { ::stockbook::Stamp::from_raw(0, 0, [].as_ptr()) }
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_placeholder_matches_stamp_kind() {
+        let masked = placeholder(&error(), StampKind::Masked).to_string();
+        let sized = placeholder(&error(), StampKind::Sized).to_string();
+
+        assert!(masked.contains(
+            &quote!(::stockbook::MaskedStamp::from_raw(
+                0,
+                0,
+                [].as_ptr(),
+                [].as_ptr()
+            ))
+            .to_string()
+        ));
+        assert!(sized.contains(&quote!(::stockbook::SizedStamp::from_raw([].as_ptr())).to_string()));
+        assert!(masked.contains("\"image not found\""));
+        assert!(sized.contains("\"image not found\""));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    args::{Args, Spanned},
+    bitmap::Bitmap,
+    emit::PixelData,
+    report,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::path::{Path, PathBuf};
+use syn::parse::{Error, Result};
+
+/// Font produced by one of the font macros &mdash; the compile-time counterpart of a
+/// `Font`.
+pub(crate) struct Font {
+    glyphs: Vec<Glyph>,
+    metrics: Metrics,
+    fallback: Option<char>,
+    /// File that the font was included from.
+    source: PathBuf,
+}
+
+/// Vertical metrics of a font, in pixels.
+pub(crate) struct Metrics {
+    pub(crate) ascent: usize,
+    pub(crate) descent: usize,
+    pub(crate) line_height: usize,
+}
+
+/// The compile-time counterpart of a `Glyph`, along with its character.
+pub(crate) struct Glyph {
+    pub(crate) char: char,
+    pub(crate) bitmap: Bitmap,
+    pub(crate) offset: [isize; 2],
+    pub(crate) advance: usize,
+}
+
+/// Arguments shared by all font macros.
+pub(crate) struct FontArgs {
+    pub(crate) chars: Option<Spanned<Vec<char>>>,
+    pub(crate) fallback: Option<Spanned<char>>,
+    pub(crate) letter_spacing: usize,
+    pub(crate) line_spacing: usize,
+}
+
+impl FontArgs {
+    pub(crate) fn from_args(args: &mut Args) -> Result<Self> {
+        let chars = args.chars("chars")?;
+        let fallback = args.char("fallback")?;
+        let letter_spacing = args
+            .int::<usize>("letter_spacing")?
+            .map_or(0, |letter_spacing| letter_spacing.value);
+        let line_spacing = args
+            .int::<usize>("line_spacing")?
+            .map_or(0, |line_spacing| line_spacing.value);
+
+        Ok(Self {
+            chars,
+            fallback,
+            letter_spacing,
+            line_spacing,
+        })
+    }
+
+    /// Characters to include out of the `available` ones &mdash; the requested ones,
+    /// or the `default` ones if none were requested, and the fallback. Fails if any of
+    /// the requested characters isn't available.
+    pub(crate) fn subset(&self, available: &[char], default: Vec<char>) -> Result<Vec<char>> {
+        let mut subset = match &self.chars {
+            Some(Spanned { value: chars, span }) => {
+                if let Some(missing) = chars.iter().find(|c| !available.contains(c)) {
+                    return Err(Error::new(
+                        *span,
+                        format!("the font has no glyph for {:?}", missing),
+                    ));
+                }
+                chars.clone()
+            }
+            None => default,
+        };
+
+        subset.extend(self.fallback.as_ref().map(|fallback| fallback.value));
+        subset.sort_unstable();
+        subset.dedup();
+
+        Ok(subset)
+    }
+}
+
+impl Font {
+    /// Checks that every character has one glyph, and that the `fallback` character
+    /// has any. Duplicate characters are reported at `span`.
+    pub(crate) fn new(
+        mut glyphs: Vec<Glyph>,
+        metrics: Metrics,
+        fallback: Option<Spanned<char>>,
+        source: PathBuf,
+        span: Span,
+    ) -> Result<Self> {
+        glyphs.sort_by_key(|glyph| glyph.char);
+
+        if let Some(pair) = glyphs.windows(2).find(|pair| pair[0].char == pair[1].char) {
+            return Err(Error::new(
+                span,
+                format!("duplicate character {:?}", pair[0].char),
+            ));
+        }
+
+        if let Some(Spanned { value, span }) = fallback {
+            if !glyphs.iter().any(|glyph| glyph.char == value) {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "the font has no glyph for the fallback character {:?}",
+                        value
+                    ),
+                ));
+            }
+        }
+
+        Ok(Self {
+            glyphs,
+            metrics,
+            fallback: fallback.map(|fallback| fallback.value),
+            source,
+        })
+    }
+
+    /// File that the font was included from.
+    pub(crate) fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Adds every glyph to the report, named after its character.
+    pub(crate) fn record(&self, span: Span) -> Result<()> {
+        report::record(
+            span,
+            Some(&self.source),
+            self.glyphs
+                .iter()
+                .map(|glyph| (glyph.char.to_string(), &glyph.bitmap)),
+            &mut PixelData::default(),
+        )
+    }
+
+    /// Glyph of `c`, or of the fallback character if the font doesn't have one.
+    pub(crate) fn glyph(&self, c: char) -> Option<&Glyph> {
+        let find = |c: char| {
+            self.glyphs
+                .binary_search_by_key(&c, |glyph| glyph.char)
+                .ok()
+                .map(|index| &self.glyphs[index])
+        };
+
+        find(c).or_else(|| self.fallback.and_then(find))
+    }
+
+    pub(crate) fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
+
+impl Glyph {
+    /// Trims the columns without any white pixels off both sides of the glyph, and
+    /// makes it advance the pen by its remaining width plus `letter_spacing`. Glyphs
+    /// without any white pixels, like the space, are left as they are.
+    pub(crate) fn trim(&mut self, letter_spacing: usize) {
+        let bitmap = &self.bitmap;
+        let is_inked = |x: usize| (0..bitmap.height).any(|y| bitmap.is_white(x, y));
+
+        let Some(first) = (0..bitmap.width).find(|&x| is_inked(x)) else {
+            return;
+        };
+        let last = (0..bitmap.width).rfind(|&x| is_inked(x)).unwrap_or(first);
+        let width = last - first + 1;
+
+        self.bitmap = bitmap.crop(first, 0, width, bitmap.height);
+        self.advance = width + letter_spacing;
+    }
+}
+
+impl ToTokens for Font {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut pixel_data = PixelData::default();
+        let glyphs = self
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                let stamp = pixel_data.push(&glyph.bitmap);
+                let [offset_x, offset_y] = glyph.offset;
+                let advance = glyph.advance;
+
+                quote! {
+                    ::stockbook::Glyph::new(#stamp, [#offset_x, #offset_y], #advance)
+                }
+            })
+            .collect::<Vec<_>>();
+        let items = pixel_data.items();
+        let glyph_count = glyphs.len();
+
+        // Runs of consecutive characters, as (first, last, index of the first glyph)
+        let mut runs = Vec::<(char, char, usize)>::new();
+        for (index, glyph) in self.glyphs.iter().enumerate() {
+            match runs.last_mut() {
+                Some((_, last, _)) if *last as u32 + 1 == glyph.char as u32 => {
+                    *last = glyph.char;
+                }
+                _ => runs.push((glyph.char, glyph.char, index)),
+            }
+        }
+        let ranges = runs
+            .iter()
+            .map(|(first, last, index)| quote!(::stockbook::CharRange::new(#first, #last, #index)));
+        let range_count = runs.len();
+
+        let Metrics {
+            ascent,
+            descent,
+            line_height,
+        } = self.metrics;
+        let fallback = match self.fallback {
+            Some(fallback) => quote!(::core::option::Option::Some(#fallback)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        tokens.extend(quote! {
+            {
+                #items
+
+                static GLYPHS: [::stockbook::Glyph; #glyph_count] = unsafe { [#(#glyphs),*] };
+                static RANGES: [::stockbook::CharRange; #range_count] = [#(#ranges),*];
+
+                ::stockbook::Font::new(
+                    &GLYPHS,
+                    &RANGES,
+                    #ascent,
+                    #descent,
+                    #line_height,
+                    #fallback,
+                )
+            }
+        });
+    }
+}
+
+/// Fails if the `transparent` argument is present, since the black pixels of a glyph
+/// are its background anyway.
+pub(crate) fn reject_transparent(args: &mut Args) -> Result<()> {
+    match args.string("transparent")? {
+        Some(lit) => Err(Error::new(
+            lit.span(),
+            "fonts can't be `transparent`, black pixels are treated as the background",
+        )),
+        None => Ok(()),
+    }
+}
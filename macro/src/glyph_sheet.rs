@@ -0,0 +1,144 @@
+use crate::{
+    args::{Args, Spanned},
+    font::{self, Font, FontArgs, Glyph, Metrics},
+    options::Options,
+    sheet::Layout,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `font!` macro.
+pub(crate) struct GlyphSheet {
+    font: Font,
+}
+
+/// Arguments of the `font!` macro, except for the path of the image.
+pub(crate) struct GlyphSheetArgs {
+    layout: Layout,
+    font_args: FontArgs,
+    proportional: bool,
+    options: Options,
+    span: Span,
+}
+
+impl Parse for GlyphSheet {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let sheet_args = GlyphSheetArgs::from_args(&mut args, &lit_str, lit_str.span())?;
+        args.finish()?;
+
+        let font = sheet_args.load(&lit_str)?;
+        font.record(lit_str.span())?;
+
+        Ok(Self { font })
+    }
+}
+
+impl GlyphSheetArgs {
+    /// Takes the arguments of the image at the path written in `lit_str`, reporting
+    /// errors that aren't specific to any of them at `span`.
+    pub(crate) fn from_args(args: &mut Args, lit_str: &LitStr, span: Span) -> Result<Self> {
+        let layout = Layout::from_args(args, "glyph", span)?;
+        let font_args = FontArgs::from_args(args)?;
+        let proportional = args.flag("proportional")?;
+        font::reject_transparent(args)?;
+        let options = Options::from_args(args, lit_str)?;
+
+        Ok(Self {
+            layout,
+            font_args,
+            proportional,
+            options,
+            span,
+        })
+    }
+
+    /// Slices the image at the path written in `lit_str` into a font.
+    pub(crate) fn load(self, lit_str: &LitStr) -> Result<Font> {
+        let Self {
+            layout,
+            font_args:
+                FontArgs {
+                    chars,
+                    fallback,
+                    letter_spacing,
+                    line_spacing,
+                },
+            proportional,
+            options,
+            span,
+        } = self;
+
+        let chars = chars.unwrap_or_else(|| Spanned {
+            value: (' '..='~').collect(),
+            span,
+        });
+
+        let path = options.resolve(lit_str)?;
+        let img = options.read_sheet(&path, lit_str.span())?;
+        let positions = layout.positions(&img, lit_str.span())?;
+
+        if chars.value.len() > positions.len() {
+            return Err(Error::new(
+                chars.span,
+                format!(
+                    "the image only has {} glyphs, but {} characters were given",
+                    positions.len(),
+                    chars.value.len()
+                ),
+            ));
+        }
+
+        let glyphs = chars
+            .value
+            .iter()
+            .zip(positions)
+            .map(|(&c, (x, y))| {
+                let bitmap = options
+                    .bitmap(layout.crop(&img, (x, y)), span)
+                    .map_err(|error| {
+                        Error::new(
+                            error.span(),
+                            format!("in glyph {:?} at {},{}: {}", c, x, y, error),
+                        )
+                    })?;
+
+                let mut glyph = Glyph {
+                    char: c,
+                    advance: bitmap.width + letter_spacing,
+                    offset: [0, 0],
+                    bitmap,
+                };
+                if proportional {
+                    glyph.trim(letter_spacing);
+                }
+
+                Ok(glyph)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let height = glyphs
+            .iter()
+            .map(|glyph| glyph.bitmap.height)
+            .max()
+            .unwrap_or(0);
+        let metrics = Metrics {
+            ascent: height,
+            descent: 0,
+            line_height: height + line_spacing,
+        };
+
+        Font::new(glyphs, metrics, fallback, path, chars.span)
+    }
+}
+
+impl ToTokens for GlyphSheet {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.font.to_tokens(tokens);
+    }
+}
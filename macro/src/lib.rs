@@ -1,26 +1,71 @@
-#![cfg_attr(use_unstable_features, feature(track_path))]
+#![cfg_attr(
+    use_unstable_features,
+    feature(proc_macro_tracked_path, proc_macro_tracked_env)
+)]
 
-use image::{GenericImageView as _, Pixel as _};
+mod animation;
+mod args;
+mod bdf;
+mod bitmap;
+mod convert;
+mod dither;
+mod emit;
+mod font;
+mod glyph_sheet;
+mod limits;
+mod mask;
+mod options;
+mod path;
+mod pbm;
+mod report;
+mod scale;
+mod sheet;
+mod stamp;
+mod stamp_ascii;
+mod stamp_enum;
+mod stamps;
+#[cfg(feature = "svg")]
+mod svg;
+mod text_stamp;
+mod transform;
+#[cfg(feature = "ttf")]
+mod ttf;
+mod xbm;
+
+use animation::Animation;
+use bdf::BdfFont;
+use glyph_sheet::GlyphSheet;
 use proc_macro::TokenStream;
-use proc_macro2::{Span, TokenStream as TokenStream2};
-use quote::{quote, ToTokens};
+use quote::quote;
+use sheet::Sheet;
+use stamp::Stamp;
+use stamp_ascii::StampAscii;
+use stamp_enum::StampEnum;
+use stamps::Stamps;
 use std::path::Path;
-use syn::{
-    parse::{Error, Parse, ParseStream, Result},
-    parse_macro_input, LitStr,
-};
+use syn::parse_macro_input;
+use text_stamp::TextStamp;
+#[cfg(feature = "ttf")]
+use ttf::TtfFont;
 
 /// Includes an image as a [`Stamp`][Stamp].
 ///
-/// The provided path is interpreted in a platform-specific way at compile time. The
-/// image’s format is determined from the path’s file extension.
+/// The provided path is interpreted in a platform-specific way at compile time. A
+/// relative path is resolved the same way [`include_bytes!`] does, i.e. relative to
+/// the file in which the macro is invoked, and, failing that, relative to the
+/// directory containing the crate's `Cargo.toml` (see [Relative
+/// paths](#relative-paths)). The image’s format is determined from the path’s file
+/// extension.
 ///
 /// The pixels of the image must be either black (`#000000ff` or
 /// `rgba(0, 0, 0, 255)`) or white (`#ffffffff` or `rgba(255, 255, 255, 255)`). Any
-/// other color will result in a compile-time error.
+/// other color will result in a compile-time error, unless the image is binarized
+/// as described in [Converting colors](#converting-colors).
 ///
 /// This macro will encode the image and yield an expression of type
-/// [`Stamp`][Stamp] with the pixel data included.
+/// [`Stamp`][Stamp] with the pixel data included, of type
+/// [`MaskedStamp`][MaskedStamp] if the image has [transparency](#transparency), or
+/// of type [`SizedStamp`][SizedStamp] if it's [sized](#sized-stamps).
 ///
 /// If the `"progmem"` feature is enabled and the target architecture is set to
 /// `avr`, the pixel data will be placed into the `.progmem.data` section using the
@@ -39,6 +84,219 @@ use syn::{
 ///
 /// Compiling `main.rs` is going to statically embed the image in the binary.
 ///
+/// Besides the formats supported by the [image](https://docs.rs/image) crate, X11
+/// bitmaps (`.xbm`), in both the X11 and the X10 variant, and PBM images (`.pbm`),
+/// in both the plain and the raw variant, are supported. Set bits of the former, and
+/// ones of the latter, become black.
+///
+/// # Converting colors
+///
+/// Images that aren't strictly black and white, e.g. anti-aliased or grayscale ones,
+/// can be binarized with the `threshold` argument. Pixels whose luma is at least the
+/// given value (between 0 and 255) become white, and the rest become black:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static IMAGE: Stamp = stamp!("anti_aliased.png", threshold = 128);
+/// ```
+///
+/// The value compared against the threshold can be taken from a single channel
+/// instead of the luma with the `channel` argument, which accepts `"luma"` (the
+/// default), `"red"`, `"green"`, `"blue"`, or `"alpha"`:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static SILHOUETTE: Stamp = stamp!("sprite.png", threshold = 1, channel = "alpha");
+/// ```
+///
+/// Photos and gradients are better served by dithering, selected with the `dither`
+/// argument:
+///
+/// - `"floyd-steinberg"` &mdash; Floyd–Steinberg error diffusion,
+/// - `"atkinson"` &mdash; Atkinson error diffusion, which has higher contrast but
+///   loses detail in very dark and very bright areas,
+/// - `"bayer2x2"`, `"bayer4x4"`, `"bayer8x8"` &mdash; ordered dithering with a
+///   Bayer matrix of the given size.
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static SPLASH: Stamp = stamp!("splash.jpg", dither = "floyd-steinberg");
+/// ```
+///
+/// Dithering also takes `channel` into account. When combined with `threshold`, the
+/// threshold is used as the midpoint between black and white, which defaults to 128.
+///
+/// Two-color images that use other colors than black and white can be mapped with
+/// the `black` and `white` arguments, which accept colors in the `"#rrggbb"` or
+/// `"#rrggbbaa"` form. Pixels of any other color still result in a compile-time
+/// error:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static GAME_BOY: Stamp = stamp!("sprite.png", black = "#0f380f", white = "#9bbc0f");
+/// ```
+///
+/// Finally, the `invert` flag swaps black and white in the result of any of the
+/// above, e.g. to match the polarity of a display:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static INVERTED: Stamp = stamp!("image.png", invert);
+/// ```
+///
+/// If an image can't be converted, the compile-time error summarizes the pixels
+/// that are neither black nor white, by color and with a few of their coordinates,
+/// and suggests the arguments that would likely fix it. The macro still yields an
+/// empty stamp, so that the rest of the crate keeps being checked for errors.
+///
+/// # Cropping
+///
+/// The `region` argument embeds only a part of the image, given as an `(x, y,
+/// width, height)` tuple, where _(x, y)_ is the top-left corner of the region. This
+/// way many images can be kept in a single file:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static OK_BUTTON: Stamp = stamp!("ui.png", region = (0, 0, 24, 12));
+/// static CANCEL_BUTTON: Stamp = stamp!("ui.png", region = (24, 0, 36, 12));
+/// ```
+///
+/// Only the pixels within the region have to satisfy the color requirements. A
+/// region that doesn't fit in the image results in a compile-time error.
+///
+/// # Rotating and flipping
+///
+/// The `rotate` argument rotates the image clockwise by 90, 180, or 270 degrees, and
+/// the `flip_x` and `flip_y` flags mirror it horizontally and vertically,
+/// respectively. The size of the resulting stamp reflects the transformation:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static PORTRAIT: Stamp = stamp!("landscape_16x12.png", rotate = 90); // 12x16
+/// static FACING_LEFT: Stamp = stamp!("facing_right.png", flip_x);
+/// ```
+///
+/// The image is rotated first and flipped second, and both happen after it's
+/// [cropped](#cropping).
+///
+/// # Scaling
+///
+/// The `scale` argument upscales the image by an integer factor, turning every pixel
+/// into a square of pixels:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static ICON: Stamp = stamp!("icon_8x8.png");
+/// static ICON_LARGE: Stamp = stamp!("icon_8x8.png", scale = 2); // 16x16
+/// ```
+///
+/// The upscaled image may have at most 2<sup>24</sup> pixels, e.g. 4096x4096.
+///
+/// Pixel art can be upscaled with smoother edges by selecting a different algorithm
+/// with the `scaler` argument:
+///
+/// - `"nearest"` &mdash; the default, supports any factor,
+/// - `"epx"` or `"scale2x"` &mdash; Eric's Pixel Expansion, also known as Scale2x,
+///   supports factors that are powers of two,
+/// - `"scale3x"` &mdash; Scale3x, supports factors that are powers of three.
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static ICON_SMOOTH: Stamp = stamp!("icon_8x8.png", scale = 2, scaler = "epx");
+/// ```
+///
+/// Alternatively, the `size` argument resizes the image to an arbitrary `(width,
+/// height)`, blending the colors of neighbouring pixels. Since that produces colors
+/// other than black and white, a resized image is [thresholded](#converting-colors)
+/// at 128 unless `threshold` or `dither` say otherwise:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static THUMBNAIL: Stamp = stamp!("photo.jpg", size = (32, 24), dither = "atkinson");
+/// ```
+///
+/// The image is resized right after it's [cropped](#cropping), and upscaled after
+/// it's [rotated and flipped](#rotating-and-flipping).
+///
+/// # Transparency
+///
+/// The `transparent` argument adds a second bitplane to the encoded image, which
+/// marks every pixel as either opaque or transparent, and makes the macro yield a
+/// [`MaskedStamp`][MaskedStamp] instead of a [`Stamp`][Stamp]. The argument accepts
+/// either `"alpha"`, in which case pixels with an alpha below 128 are transparent,
+/// or a color key in the `"#rrggbb"` or `"#rrggbbaa"` form, in which case pixels of
+/// exactly that color are transparent:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, MaskedStamp};
+///
+/// static SPRITE: MaskedStamp = stamp!("sprite.png", transparent = "alpha");
+/// static KEYED_SPRITE: MaskedStamp = stamp!("keyed_sprite.png", transparent = "#ff00ff");
+/// ```
+///
+/// Transparent pixels are exempt from the black-and-white requirement, and the alpha
+/// of the opaque ones is ignored. For that reason, `channel = "alpha"`, as well as
+/// `black` and `white` colors with an alpha other than `ff`, can't be used together
+/// with `transparent`.
+///
+/// # Sized stamps
+///
+/// The `sized` argument makes the macro yield a [`SizedStamp`][SizedStamp] instead of
+/// a [`Stamp`][Stamp], with the width and height of the image as its type
+/// parameters. An image of an unexpected size is then caught by the type checker,
+/// e.g. when an icon has to fit a slot of a given size:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, SizedStamp};
+///
+/// static WIFI: SizedStamp<16, 16> = stamp!("wifi.png", sized);
+/// ```
+///
+/// A sized stamp is borrowed as a plain `Stamp` with its `stamp` method, or converted
+/// into one with `Stamp::from`. The `sized` argument can't be combined with
+/// [`transparent`](#transparency).
+///
+/// # Limits
+///
+/// The `max_bytes` argument limits the number of bytes that the encoded image takes,
+/// including its transparency mask, and the `max_size` argument limits its `(width,
+/// height)` after all of the transformations. An image that exceeds either of them
+/// results in a compile-time error, e.g. to keep an asset within the program memory
+/// or the display:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static BOOT: Stamp = stamp!("boot.png", max_bytes = 1024, max_size = (128, 64));
+/// ```
+///
+/// Both limits apply to every stamp separately, e.g. to every tile of a sprite sheet.
+///
+/// # Vector images
+///
+/// With the `svg` feature, images with the `.svg` extension are rasterized at
+/// compile time. A vector image is rendered at its own size, or directly at the
+/// given [`size`](#scaling), and composited onto a white background, unless it's
+/// [transparent](#transparency). Since the rendered shapes are anti-aliased, the
+/// result is [thresholded](#converting-colors) at 128 unless `threshold` or `dither`
+/// say otherwise:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static ICON: Stamp = stamp!("icon.svg", size = (16, 16));
+/// ```
+///
 /// # Quirks
 ///
 /// ## Input
@@ -59,151 +317,567 @@ use syn::{
 ///
 /// ## Relative paths
 ///
-/// Note that unlike [`include_bytes!`], the file is not located relative to the
-/// current file. Generally, when invoking [`stamp!`] you should try to avoid using
-/// relative paths because `rustc` makes no guarantees about the current directory
-/// when it is running a procedural macro.
+/// By default, a relative path is first looked up relative to the directory of the
+/// current file, and then relative to `CARGO_MANIFEST_DIR`, so that both of these
+/// work from within `src/lib.rs`:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static NEXT_TO_SOURCE: Stamp = stamp!("image.png"); // src/image.png
+/// static IN_ASSETS: Stamp = stamp!("assets/image.png"); // assets/image.png
+/// ```
+///
+/// Either of these lookups can be requested explicitly with the `relative_to`
+/// argument, which accepts `"file"` or `"manifest"`:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp, Stamp};
+///
+/// static IMAGE: Stamp = stamp!("assets/image.png", relative_to = "manifest");
+/// ```
+///
+/// If the image can't be found, the compile-time error lists every path that was
+/// tried.
+///
+/// ## Repeated images
+///
+/// Every invocation embeds its own copy of the pixel data, even if another one
+/// includes the same image, and it's up to the linker whether the copies get merged.
+/// Sharing them across invocations would require the expansions to refer to each
+/// other's statics by name, which breaks linking of crates that include the same
+/// image, and reading the stamps in [const contexts](index.html#const-contexts).
+///
+/// To store an image that's used in several places only once, include it once and
+/// refer to that stamp instead:
+///
+/// ```rust,ignore
+/// // src/icons.rs
+/// use stockbook::{stamp, Stamp};
+///
+/// pub static ARROW: Stamp = stamp!("arrow.png");
+///
+/// // src/menu.rs
+/// use crate::icons::ARROW;
+/// ```
+///
+/// Within a single invocation of [`stamp_sheet!`], [`font!`], [`stamp_enum!`] or
+/// [`animation!`], identical tiles, glyphs or frames are stored only once.
 ///
 /// [Stamp]: struct.Stamp.html
+/// [MaskedStamp]: struct.MaskedStamp.html
+/// [SizedStamp]: struct.SizedStamp.html
 #[proc_macro]
 pub fn stamp(input: TokenStream) -> TokenStream {
     let stamp = parse_macro_input!(input as Stamp);
     quote! { #stamp }.into()
 }
 
-struct Stamp {
-    width: usize,
-    height: usize,
-    data: Vec<u8>,
+/// Includes a sprite sheet as an array of [`Stamp`][Stamp]s.
+///
+/// The image is sliced into tiles of the size given by the `tile` argument, which are
+/// then returned in row-major order, i.e. left to right, then top to bottom. The
+/// resulting array has one element per tile that fits in the image as a whole.
+///
+/// All of the tiles share a single array of pixel data, in which identical tiles are
+/// stored only once.
+///
+/// # Examples
+///
+/// Assume there's a 32x16 pixel image `sheet.png` in the same directory as a
+/// `main.rs` with the following contents:
+///
+/// ```rust,ignore
+/// use stockbook::{stamp_sheet, Stamp};
+///
+/// static TILES: [Stamp; 8] = stamp_sheet!("sheet.png", tile = (8, 8));
+/// ```
+///
+/// # Arguments
+///
+/// - `tile = (width, height)` &mdash; size of a single tile; required,
+/// - `margin = n` &mdash; number of pixels between the top-left corner of the image
+///   and the first tile,
+/// - `spacing = n` &mdash; number of pixels between neighbouring tiles,
+/// - `count = n` &mdash; number of tiles to include, for sheets whose last row isn't
+///   full.
+///
+/// ```rust,ignore
+/// use stockbook::{stamp_sheet, Stamp};
+///
+/// static FRAMES: [Stamp; 6] = stamp_sheet!(
+///     "walk_cycle.png",
+///     tile = (16, 24),
+///     margin = 1,
+///     spacing = 2,
+///     count = 6,
+/// );
+/// ```
+///
+/// All of the arguments of [`stamp!`] are supported too, and are applied to every
+/// tile separately. In particular, `transparent` makes the macro yield an array of
+/// [`MaskedStamp`][MaskedStamp]s.
+///
+/// [Stamp]: struct.Stamp.html
+/// [MaskedStamp]: struct.MaskedStamp.html
+#[proc_macro]
+pub fn stamp_sheet(input: TokenStream) -> TokenStream {
+    let sheet = parse_macro_input!(input as Sheet);
+    quote! { #sheet }.into()
 }
 
-#[derive(Default, Clone, Copy)]
-enum Color {
-    #[default]
-    Black,
-    White,
+/// Includes every image matching a glob pattern as a module of named
+/// [`Stamp`][Stamp]s.
+///
+/// The macro takes a module declaration, followed by the pattern, and expands to a
+/// module with one `static` per matched file, named after the file's stem in upper
+/// case. The pattern is resolved the same way a path passed to [`stamp!`] is, and
+/// supports the usual `?`, `*`, `**` and `[...]` wildcards.
+///
+/// A file whose stem doesn't make a valid identifier, e.g. `wi-fi.png` or
+/// `5g.png`, as well as two files whose stems only differ in case or extension,
+/// result in a compile-time error.
+///
+/// # Examples
+///
+/// Assume there are two images, `wifi.png` and `battery.png`, in the `icons`
+/// directory next to a `main.rs` with the following contents:
+///
+/// ```rust,ignore
+/// use stockbook::{stamps, Stamp};
+///
+/// stamps!(pub mod icons, "icons/*.png");
+///
+/// static STATUS_BAR: [&Stamp; 2] = [&icons::WIFI, &icons::BATTERY];
+/// ```
+///
+/// Attributes, including doc comments, are applied to the module:
+///
+/// ```rust,ignore
+/// use stockbook::stamps;
+///
+/// stamps!(
+///     /// Icons of the status bar.
+///     pub mod icons,
+///     "icons/*.png",
+///     threshold = 128,
+/// );
+/// ```
+///
+/// # Arguments
+///
+/// All of the arguments of [`stamp!`] are supported, and are applied to every image
+/// separately. In particular, `transparent` makes the macro include
+/// [`MaskedStamp`][MaskedStamp]s.
+///
+/// # Quirks
+///
+/// Only the files that matched the pattern are tracked for changes, so a file added
+/// to the directory is only picked up once the crate is rebuilt for another reason.
+///
+/// [Stamp]: struct.Stamp.html
+/// [MaskedStamp]: struct.MaskedStamp.html
+#[proc_macro]
+pub fn stamps(input: TokenStream) -> TokenStream {
+    let stamps = parse_macro_input!(input as Stamps);
+    quote! { #stamps }.into()
 }
 
-impl Parse for Stamp {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let lit_str = input.parse::<LitStr>()?;
-        let path_str = lit_str.value();
-        let path = Path::new(&path_str);
-
-        track_file_if_available(path);
-
-        let img = image::open(path).map_err(|error| {
-            Error::new(
-                input.span(),
-                format!("couldn't read {}: {}", path.display(), error),
-            )
-        })?;
-
-        let (width, height) = img.dimensions();
-        let (width, height) = (width as usize, height as usize);
-
-        let mut colors = vec![Default::default(); width * height];
-
-        for (x, y, color) in img.pixels() {
-            let channels = color.channels();
-            let [r, g, b, a] = [channels[0], channels[1], channels[2], channels[3]];
-
-            let color = match [r, g, b, a] {
-                [0, 0, 0, 255] => Color::Black,
-
-                [255, 255, 255, 255] => Color::White,
+/// Includes a glyph sheet as a [`Font`][Font].
+///
+/// The image is sliced into glyphs of the size given by the `glyph` argument, the
+/// same way [`stamp_sheet!`] slices tiles, and the glyphs are assigned the
+/// characters given by the `chars` argument, in order. White pixels of a glyph are
+/// its ink, and black ones are its background.
+///
+/// All of the glyphs share a single array of pixel data, in which identical glyphs
+/// are stored only once.
+///
+/// # Examples
+///
+/// Assume there's a glyph sheet `font.png` with 5x7 pixel glyphs of every printable
+/// ASCII character in the same directory as a `main.rs` with the following
+/// contents:
+///
+/// ```rust,ignore
+/// use stockbook::{font, Color, Font};
+///
+/// static FONT: Font = font!("font.png", glyph = (5, 7), letter_spacing = 1);
+///
+/// fn draw_greeting() {
+///     for (x, y, color) in FONT.text("Hello,\nworld!") {
+///         if color == Color::White {
+///             draw_pixel_at(x, y);
+///         }
+///     }
+/// }
+/// ```
+///
+/// # Arguments
+///
+/// - `glyph = (width, height)` &mdash; size of a single glyph in the sheet;
+///   required,
+/// - `chars = "..."` &mdash; characters of the glyphs, in row-major order, given as a
+///   string, an inclusive range like `'a'..='z'`, or an array of those like
+///   `['0'..='9', "+-*/"]`; the printable ASCII characters, `' '..='~'`, by default,
+/// - `fallback = 'c'` &mdash; character whose glyph is used for characters that the
+///   font doesn't have,
+/// - `proportional` &mdash; trims the empty columns off both sides of every glyph,
+///   so that narrow glyphs take less space; glyphs without any white pixels, like
+///   the space, keep their full width,
+/// - `letter_spacing = n` &mdash; number of pixels between consecutive glyphs,
+/// - `line_spacing = n` &mdash; number of pixels between consecutive lines,
+/// - `margin = n` and `spacing = n` &mdash; same as in [`stamp_sheet!`].
+///
+/// ```rust,ignore
+/// use stockbook::{font, Font};
+///
+/// static FONT: Font = font!(
+///     "greek.png",
+///     glyph = (6, 8),
+///     chars = [' ', 'Α'..='Ρ', 'Σ'..='Ω', 'α'..='ω', '?'],
+///     fallback = '?',
+///     proportional,
+///     letter_spacing = 1,
+/// );
+/// ```
+///
+/// All of the arguments of [`stamp!`], except for `transparent`, are supported too,
+/// and are applied to every glyph separately.
+///
+/// [Font]: struct.Font.html
+#[proc_macro]
+pub fn font(input: TokenStream) -> TokenStream {
+    let glyph_sheet = parse_macro_input!(input as GlyphSheet);
+    quote! { #glyph_sheet }.into()
+}
 
-                _ => {
-                    return Err(Error::new(
-                        input.span(),
-                        format!(
-                            "invalid pixel at {},{} (#{:02x}{:02x}{:02x}{:02x})",
-                            x, y, r, g, b, a
-                        ),
-                    ))
-                }
-            };
+/// Includes a font in the [BDF](https://en.wikipedia.org/wiki/Glyph_Bitmap_Distribution_Format)
+/// format as a [`Font`][Font].
+///
+/// Every glyph keeps its own bounding box, so that only the pixels within it are
+/// included, as well as its offset and advance, so that text is laid out the way
+/// the font intends. Set bits of the font's bitmaps become white pixels.
+///
+/// The characters of the glyphs are taken from their `ENCODING`s, which are assumed
+/// to be Unicode code points, as is the case with fonts in the `ISO10646-1` and
+/// `ISO8859-1` encodings. Glyphs without an encoding are skipped.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use stockbook::{bdf_font, Font};
+///
+/// static TERMINUS: Font = bdf_font!("ter-u12n.bdf");
+/// ```
+///
+/// # Arguments
+///
+/// - `chars = "..."` &mdash; characters to include, in the same forms as in
+///   [`font!`]; all of the font's characters by default, which can take a lot of
+///   space for fonts that cover much of Unicode,
+/// - `fallback = 'c'` &mdash; character whose glyph is used for characters that the
+///   font doesn't have; always included,
+/// - `letter_spacing = n` &mdash; number of extra pixels between consecutive glyphs,
+/// - `line_spacing = n` &mdash; number of extra pixels between consecutive lines,
+/// - `relative_to = "..."` &mdash; same as in [`stamp!`].
+///
+/// ```rust,ignore
+/// use stockbook::{bdf_font, Font};
+///
+/// static DIGITS: Font = bdf_font!("spleen-5x8.bdf", chars = ['0'..='9', ".:-"]);
+/// ```
+///
+/// Requesting a character that the font doesn't have results in a compile-time
+/// error.
+///
+/// [Font]: struct.Font.html
+#[proc_macro]
+pub fn bdf_font(input: TokenStream) -> TokenStream {
+    let bdf_font = parse_macro_input!(input as BdfFont);
+    quote! { #bdf_font }.into()
+}
 
-            let index = y as usize * width + x as usize;
-            colors[index] = color;
-        }
+/// Rasterizes a TrueType or OpenType font into a [`Font`][Font] at compile time.
+///
+/// Every glyph is rendered at the given size and thresholded, so that pixels at
+/// least half covered by the glyph's outline become white. The font's ascent,
+/// descent, line gap, and the glyphs' advances are rounded to whole pixels. Only
+/// available with the `ttf` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use stockbook::{ttf_font, Font};
+///
+/// static INTER: Font = ttf_font!("Inter-Regular.ttf", size = 12);
+/// ```
+///
+/// # Arguments
+///
+/// - `size = n` &mdash; size of the em square in pixels; required,
+/// - `threshold = n` &mdash; coverage, from 0 to 255, at which a pixel becomes
+///   white; 128 by default,
+/// - `chars = "..."` &mdash; characters to include, in the same forms as in
+///   [`font!`]; the printable ASCII characters that the font has by default,
+/// - `fallback = 'c'` &mdash; character whose glyph is used for characters that the
+///   font doesn't have; always included,
+/// - `letter_spacing = n` &mdash; number of extra pixels between consecutive glyphs,
+/// - `line_spacing = n` &mdash; number of extra pixels between consecutive lines,
+/// - `relative_to = "..."` &mdash; same as in [`stamp!`].
+///
+/// ```rust,ignore
+/// use stockbook::{ttf_font, Font};
+///
+/// static CLOCK: Font = ttf_font!("Inter-Bold.ttf", size = 32, chars = ['0'..='9', ':']);
+/// ```
+///
+/// Outline fonts are designed to be anti-aliased, so small sizes may need some
+/// tuning of the `threshold` to look right. Requesting a character that the font
+/// doesn't have results in a compile-time error.
+///
+/// [Font]: struct.Font.html
+#[cfg(feature = "ttf")]
+#[proc_macro]
+pub fn ttf_font(input: TokenStream) -> TokenStream {
+    let ttf_font = parse_macro_input!(input as TtfFont);
+    quote! { #ttf_font }.into()
+}
 
-        let mut data = vec![0u8; encoding_len(width * height)];
+/// Renders a piece of text into a single [`Stamp`][Stamp] at compile time.
+///
+/// The text is laid out with a font the same way [`Font::text`][Font::text] would,
+/// so that the stamp looks the same as if the text was drawn at runtime, and is as
+/// large as [`Font::text_size`][Font::text_size] says. None of the font's glyphs
+/// are included in the program, only the rendered text.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use stockbook::{text_stamp, Stamp};
+///
+/// static LABEL: Stamp = text_stamp!("SETTINGS", font = "fonts/spleen-5x8.bdf");
+/// ```
+///
+/// # Arguments
+///
+/// - `font = "..."` &mdash; path to the font, which is included the same way as by
+///   [`bdf_font!`] if it ends with `.bdf`, by [`ttf_font!`] if it ends with `.ttf`
+///   or `.otf`, and by [`font!`] otherwise; required,
+/// - `align = "..."` &mdash; alignment of the lines of text, `"left"`, `"center"` or
+///   `"right"`; `"left"` by default,
+/// - `max_width = n` &mdash; width in pixels that lines are wrapped at, between
+///   words.
+///
+/// All of the arguments of the macro that includes the font are supported too,
+/// and are applied to the font:
+///
+/// ```rust,ignore
+/// use stockbook::{text_stamp, Stamp};
+///
+/// static HINT: Stamp = text_stamp!(
+///     "Press OK to continue",
+///     font = "fonts/5x7.png",
+///     glyph = (5, 7),
+///     letter_spacing = 1,
+///     align = "center",
+///     max_width = 64,
+/// );
+/// ```
+///
+/// A character that the font doesn't have, unless it has a `fallback`, or a word
+/// that's wider than `max_width` on its own results in a compile-time error.
+///
+/// [Stamp]: struct.Stamp.html
+/// [Font::text]: struct.Font.html#method.text
+/// [Font::text_size]: struct.Font.html#method.text_size
+#[proc_macro]
+pub fn text_stamp(input: TokenStream) -> TokenStream {
+    let text_stamp = parse_macro_input!(input as TextStamp);
+    quote! { #text_stamp }.into()
+}
 
-        for (index, color) in colors.iter().enumerate() {
-            let byte_index = index / 8;
-            let bit_index = 7 - (index % 8);
-            let byte = &mut data[byte_index];
+/// Includes ASCII art as a [`Stamp`][Stamp].
+///
+/// The art is given either inline, as a string literal, or as the path to a `.txt`
+/// file with the same contents. Every row is written as a line of `#` characters,
+/// which are white pixels, and `.` characters, which are black pixels, exactly like
+/// the bits of the data taken by `Stamp::from_raw`. Whitespace around the rows is
+/// ignored, and so are blank lines before the first row and after the last one.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use stockbook::{stamp_ascii, Stamp};
+///
+/// static CHECKMARK: Stamp = stamp_ascii!("
+///     ....#
+///     ...#.
+///     #.#..
+///     .#...
+/// ");
+/// static ARROW: Stamp = stamp_ascii!("arrow.txt");
+/// ```
+///
+/// # Arguments
+///
+/// - `relative_to = "..."` &mdash; directory that the path to a `.txt` file is
+///   relative to, the same as for [`stamp!`].
+///
+/// Rows of different widths, or characters other than `#` and `.`, result in a
+/// compile-time error. The error points at the offending row or character, when the
+/// compiler can tell where it is in the literal.
+///
+/// [Stamp]: struct.Stamp.html
+#[proc_macro]
+pub fn stamp_ascii(input: TokenStream) -> TokenStream {
+    let stamp_ascii = parse_macro_input!(input as StampAscii);
+    quote! { #stamp_ascii }.into()
+}
 
-            match color {
-                Color::Black => *byte &= !(1 << bit_index),
-                Color::White => *byte |= 1 << bit_index,
-            }
-        }
+/// Defines an enum whose variants each include an image as a [`Stamp`][Stamp].
+///
+/// The macro takes an enum declaration, in which every variant is assigned the path
+/// to its image, and expands to a fieldless enum that derives `Debug`, `Clone`,
+/// `Copy`, `PartialEq`, `Eq`, and `Hash`, along with:
+///
+/// - `ALL` &mdash; an array of every variant, in the order of declaration,
+/// - `fn stamp(&self) -> &'static Stamp` &mdash; the variant's stamp,
+/// - `fn name(&self) -> &'static str` &mdash; the variant's name, which is also what
+///   its `Display` implementation writes.
+///
+/// All of the stamps share a single array of pixel data, in which identical images
+/// are stored only once.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use stockbook::stamp_enum;
+///
+/// stamp_enum! {
+///     /// Icons of the main menu.
+///     pub enum Icon {
+///         Home = "icons/home.png",
+///         Settings = "icons/settings.png",
+///     }
+/// }
+///
+/// fn draw_menu() {
+///     for icon in Icon::ALL {
+///         let stamp = icon.stamp();
+///         /* ... */
+///     }
+/// }
+/// ```
+///
+/// # Arguments
+///
+/// A variant can be given any of the arguments of [`stamp!`] by wrapping its path
+/// in parentheses:
+///
+/// ```rust,ignore
+/// use stockbook::stamp_enum;
+///
+/// stamp_enum! {
+///     pub enum Icon {
+///         Home = "icons/home.png",
+///         Photo = ("photos/cat.jpg", size = (16, 16), dither = "atkinson"),
+///     }
+/// }
+/// ```
+///
+/// If the variants are `transparent`, then `stamp` returns a
+/// [`MaskedStamp`][MaskedStamp]. Either all or none of them have to be.
+///
+/// [Stamp]: struct.Stamp.html
+/// [MaskedStamp]: struct.MaskedStamp.html
+#[proc_macro]
+pub fn stamp_enum(input: TokenStream) -> TokenStream {
+    let stamp_enum = parse_macro_input!(input as StampEnum);
+    quote! { #stamp_enum }.into()
+}
 
-        Ok(Self {
-            width,
-            height,
-            data,
-        })
-    }
+/// Includes an animated image as an [`Animation`][Animation].
+///
+/// Every frame of the animation is decoded and encoded as a [`Stamp`][Stamp], and
+/// the frames' delays are stored in milliseconds alongside them, so that the
+/// resulting [`Animation`][Animation] can tell which frame to show at any given
+/// time. The frames are composited, i.e. every one of them covers the whole canvas
+/// of the animation, even if the file only stores the part that changed.
+///
+/// Animated GIF and APNG files are supported. A PNG file that isn't animated yields
+/// a single frame with a delay of zero.
+///
+/// All of the frames share a single array of pixel data, in which identical frames
+/// are stored only once.
+///
+/// # Examples
+///
+/// Assume there's an animated image `spinner.gif` in the same directory as a
+/// `main.rs` with the following contents:
+///
+/// ```rust,ignore
+/// use stockbook::{animation, Animation};
+///
+/// static SPINNER: Animation = animation!("spinner.gif");
+///
+/// fn draw(elapsed_ms: u32) {
+///     let frame = SPINNER.frame_at(elapsed_ms);
+///     /* ... */
+/// }
+/// ```
+///
+/// # Arguments
+///
+/// All of the arguments of [`stamp!`] are supported, and are applied to every frame
+/// separately. In particular, `transparent` makes the macro yield an
+/// `Animation<MaskedStamp>`:
+///
+/// ```rust,ignore
+/// use stockbook::{animation, Animation, MaskedStamp};
+///
+/// static SPINNER: Animation<MaskedStamp> = animation!(
+///     "spinner.gif",
+///     threshold = 128,
+///     transparent = "alpha",
+/// );
+/// ```
+///
+/// [Animation]: struct.Animation.html
+/// [Stamp]: struct.Stamp.html
+#[proc_macro]
+pub fn animation(input: TokenStream) -> TokenStream {
+    let animation = parse_macro_input!(input as Animation);
+    quote! { #animation }.into()
 }
 
-fn encoding_len(pixel_count: usize) -> usize {
-    let d = pixel_count / 8;
-    let r = pixel_count % 8;
+#[derive(Clone, Copy)]
+enum Color {
+    Black,
+    White,
+}
 
-    if r > 0 {
-        d + 1
-    } else {
-        d
+impl Color {
+    fn invert(self) -> Self {
+        match self {
+            Self::Black => Self::White,
+            Self::White => Self::Black,
+        }
     }
 }
 
 fn track_file_if_available(path: impl AsRef<Path>) {
     #[cfg(use_unstable_features)]
-    proc_macro::tracked_path::path(format!("{}", path.as_ref().display()));
+    proc_macro::tracked::path(format!("{}", path.as_ref().display()));
 
     #[cfg(not(use_unstable_features))]
     let _ = path;
 }
 
-impl ToTokens for Stamp {
-    fn to_tokens(&self, tokens: &mut TokenStream2) {
-        let width = self.width;
-        let height = self.height;
-        let array_len = self.data.len();
-        let array = syn::ExprArray {
-            attrs: Default::default(),
-            bracket_token: Default::default(),
-            elems: self
-                .data
-                .iter()
-                .map(|byte| {
-                    syn::Expr::Lit(syn::ExprLit {
-                        attrs: Default::default(),
-                        lit: syn::Lit::Int(syn::LitInt::new(&byte.to_string(), Span::call_site())),
-                    })
-                })
-                .collect(),
-        };
-
-        #[cfg(feature = "progmem")]
-        let progmem_attr = quote! {
-            #[cfg_attr(target_arch = "avr", link_section = ".progmem.data")]
-        };
-        #[cfg(not(feature = "progmem"))]
-        let progmem_attr = TokenStream2::new();
-
-        tokens.extend(quote! {
-            {
-                #progmem_attr
-                static PIXEL_DATA: [u8; #array_len] = #array;
-
-                unsafe {
-                    ::stockbook::Stamp::from_raw(#width, #height, PIXEL_DATA.as_ptr())
-                }
-            }
-        });
-    }
+fn env_var_tracked_if_available(key: &str) -> Option<String> {
+    #[cfg(use_unstable_features)]
+    return proc_macro::tracked::env_var(key).ok();
+
+    #[cfg(not(use_unstable_features))]
+    std::env::var(key).ok()
 }
@@ -0,0 +1,148 @@
+use crate::{args::Args, bitmap::Bitmap};
+use proc_macro2::Span;
+use syn::parse::{Error, Result};
+
+/// Upper bounds on the size of an encoded image, e.g. to make sure that it fits in
+/// the program memory or on the display.
+pub(crate) struct Limits {
+    max_bytes: Option<usize>,
+    max_size: Option<[usize; 2]>,
+}
+
+impl Limits {
+    pub(crate) fn from_args(args: &mut Args) -> Result<Self> {
+        let max_bytes = args.int("max_bytes")?.map(|max_bytes| max_bytes.value);
+        let max_size = args.tuple("max_size")?.map(|max_size| max_size.value);
+
+        Ok(Self {
+            max_bytes,
+            max_size,
+        })
+    }
+
+    /// Fails if an image of the given size exceeds `max_size`. Errors are reported at
+    /// `span`.
+    pub(crate) fn check_size(&self, width: usize, height: usize, span: Span) -> Result<()> {
+        match self.max_size {
+            Some([max_width, max_height]) if width > max_width || height > max_height => {
+                Err(Error::new(
+                    span,
+                    format!(
+                        "the image is {}x{} pixels, which exceeds `max_size` of {}x{}",
+                        width, height, max_width, max_height
+                    ),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if `bitmap` takes more than `max_bytes`. Errors are reported at `span`.
+    pub(crate) fn check_bytes(&self, bitmap: &Bitmap, span: Span) -> Result<()> {
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(());
+        };
+
+        let data_len = bitmap.data.len();
+        let mask_len = bitmap.mask.as_ref().map_or(0, Vec::len);
+        if data_len + mask_len <= max_bytes {
+            return Ok(());
+        }
+
+        let bytes = match bitmap.mask {
+            None => format!("{} bytes", data_len),
+            Some(_) => format!(
+                "{} bytes ({} of pixel data and {} of mask)",
+                data_len + mask_len,
+                data_len,
+                mask_len
+            ),
+        };
+
+        Err(Error::new(
+            span,
+            format!(
+                "the {}x{} image takes {}, which exceeds `max_bytes` of {}",
+                bitmap.width, bitmap.height, bytes, max_bytes
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bitmap(width: usize, height: usize, masked: bool) -> Bitmap {
+        let data = Bitmap::encode((0..width * height).map(|_| true));
+        let mask = masked.then(|| data.clone());
+
+        Bitmap {
+            width,
+            height,
+            data,
+            mask,
+        }
+    }
+
+    fn message(result: Result<()>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_size_within_max_size() {
+        let limits = Limits {
+            max_bytes: None,
+            max_size: Some([8, 4]),
+        };
+
+        assert!(limits.check_size(8, 4, Span::call_site()).is_ok());
+    }
+
+    #[test]
+    fn test_size_exceeding_max_size() {
+        let limits = Limits {
+            max_bytes: None,
+            max_size: Some([8, 4]),
+        };
+
+        assert_eq!(
+            message(limits.check_size(8, 5, Span::call_site())),
+            "the image is 8x5 pixels, which exceeds `max_size` of 8x4"
+        );
+        assert_eq!(
+            message(limits.check_size(9, 1, Span::call_site())),
+            "the image is 9x1 pixels, which exceeds `max_size` of 8x4"
+        );
+    }
+
+    #[test]
+    fn test_bytes_within_max_bytes() {
+        let limits = Limits {
+            max_bytes: Some(4),
+            max_size: None,
+        };
+
+        assert!(limits
+            .check_bytes(&bitmap(4, 4, true), Span::call_site())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_bytes_exceeding_max_bytes() {
+        let limits = Limits {
+            max_bytes: Some(3),
+            max_size: None,
+        };
+
+        assert_eq!(
+            message(limits.check_bytes(&bitmap(5, 5, false), Span::call_site())),
+            "the 5x5 image takes 4 bytes, which exceeds `max_bytes` of 3"
+        );
+        assert_eq!(
+            message(limits.check_bytes(&bitmap(4, 4, true), Span::call_site())),
+            "the 4x4 image takes 4 bytes (2 of pixel data and 2 of mask), \
+             which exceeds `max_bytes` of 3"
+        );
+    }
+}
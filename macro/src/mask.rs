@@ -0,0 +1,44 @@
+use crate::convert::parse_color;
+use image::{Rgba, RgbaImage};
+use syn::{parse::Result, LitStr};
+
+/// Source of a stamp's transparency mask.
+pub(crate) enum Mask {
+    /// Pixels with an alpha below 128 are transparent.
+    Alpha,
+    /// Pixels of exactly this color are transparent.
+    Key(Rgba<u8>),
+}
+
+impl Mask {
+    pub(crate) fn from_lit(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "alpha" => Ok(Self::Alpha),
+            _ => parse_color(lit).map(Self::Key),
+        }
+    }
+
+    /// Returns whether each pixel of `img` is opaque, in row-major order.
+    ///
+    /// Transparent pixels are replaced with `fill`, which should be a color that
+    /// passes the conversion, and opaque pixels lose their alpha, so that only their
+    /// color is converted.
+    pub(crate) fn apply(&self, img: &mut RgbaImage, fill: Rgba<u8>) -> Vec<bool> {
+        img.pixels_mut()
+            .map(|pixel| {
+                let opaque = match self {
+                    Self::Alpha => pixel.0[3] >= 128,
+                    Self::Key(key) => pixel != key,
+                };
+
+                if opaque {
+                    pixel.0[3] = 255;
+                } else {
+                    *pixel = fill;
+                }
+
+                opaque
+            })
+            .collect()
+    }
+}
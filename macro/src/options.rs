@@ -0,0 +1,169 @@
+#[cfg(feature = "svg")]
+use crate::svg;
+use crate::{
+    args::Args,
+    bitmap::Bitmap,
+    convert::Conversion,
+    limits::Limits,
+    mask::Mask,
+    path::{self, RelativeTo},
+    pbm, track_file_if_available,
+    transform::Transform,
+    xbm, Color,
+};
+use image::RgbaImage;
+use proc_macro2::Span;
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+use syn::{
+    parse::{Error, Result},
+    LitStr,
+};
+
+/// Arguments shared by all macros that embed images, which control how an image is
+/// found and turned into a [`Bitmap`].
+pub(crate) struct Options {
+    relative_to: Option<RelativeTo>,
+    transform: Transform,
+    conversion: Conversion,
+    mask: Option<Mask>,
+    limits: Limits,
+}
+
+impl Options {
+    /// Takes the arguments of the image, or images, at the path or glob pattern
+    /// written in `lit`.
+    pub(crate) fn from_args(args: &mut Args, lit: &LitStr) -> Result<Self> {
+        let relative_to = args
+            .string("relative_to")?
+            .map(|lit| RelativeTo::from_lit(&lit))
+            .transpose()?;
+        let transform = Transform::from_args(args)?;
+        // Rendered vector images are anti-aliased, just like resized images
+        let vector = has_extension(Path::new(&lit.value()), "svg");
+        let masked = args.has("transparent");
+        let conversion = Conversion::from_args(args, transform.resamples() || vector, masked)?;
+        let mask = args
+            .string("transparent")?
+            .map(|lit| Mask::from_lit(&lit))
+            .transpose()?;
+        let limits = Limits::from_args(args)?;
+
+        Ok(Self {
+            relative_to,
+            transform,
+            conversion,
+            mask,
+            limits,
+        })
+    }
+
+    /// Finds the file at the path written in `lit`, and tracks it for changes.
+    pub(crate) fn resolve(&self, lit: &LitStr) -> Result<PathBuf> {
+        let path = path::resolve(lit, self.relative_to)?;
+
+        track_file_if_available(&path);
+
+        Ok(path)
+    }
+
+    /// Finds every file matching the glob pattern written in `lit`, and tracks them
+    /// for changes.
+    pub(crate) fn glob(&self, lit: &LitStr) -> Result<Vec<PathBuf>> {
+        let paths = path::glob(lit, self.relative_to)?;
+
+        for path in &paths {
+            track_file_if_available(path);
+        }
+
+        Ok(paths)
+    }
+
+    /// Same as [`read`](Options::read), except that vector images are always
+    /// rendered at their own size, since a sheet is transformed tile by tile.
+    pub(crate) fn read_sheet(&self, path: &Path, span: Span) -> Result<RgbaImage> {
+        self.decode(path, None, span)
+    }
+
+    /// Decodes the image at `path`. Vector images are rendered right away at the
+    /// `size` that they'd be resized to, if any. Errors are reported at `span`.
+    pub(crate) fn read(&self, path: &Path, span: Span) -> Result<RgbaImage> {
+        self.decode(path, self.transform.render_size(), span)
+    }
+
+    /// Transforms and converts `img`, and checks it against the limits. Errors are
+    /// reported at `span`.
+    pub(crate) fn bitmap(&self, img: RgbaImage, span: Span) -> Result<Bitmap> {
+        let mut img = self.transform.apply(img)?;
+
+        let (width, height) = img.dimensions();
+        let (width, height) = (width as usize, height as usize);
+        self.limits.check_size(width, height, span)?;
+
+        let opaque = self
+            .mask
+            .as_ref()
+            .map(|mask| mask.apply(&mut img, self.conversion.black()));
+        let colors = self.conversion.apply(&img, span)?;
+
+        let data = Bitmap::encode(colors.iter().map(|color| matches!(color, Color::White)));
+        let mask = opaque.map(|opaque| Bitmap::encode(opaque.into_iter()));
+
+        let bitmap = Bitmap {
+            width,
+            height,
+            data,
+            mask,
+        };
+        self.limits.check_bytes(&bitmap, span)?;
+
+        Ok(bitmap)
+    }
+
+    /// Decodes the image at `path`, rendering vector images at `size`, or at their
+    /// own size if there's none.
+    fn decode(&self, path: &Path, size: Option<[u32; 2]>, span: Span) -> Result<RgbaImage> {
+        let error = |error: &dyn Display| {
+            Error::new(span, format!("couldn't read {}: {}", path.display(), error))
+        };
+
+        // X11 bitmaps and PBM images are 1-bit already, but the `image` crate doesn't
+        // read the former, and only reads some variants of the latter
+        if has_extension(path, "xbm") {
+            return fs::read_to_string(path)
+                .map_err(|io_error| error(&io_error))
+                .and_then(|source| xbm::parse(&source).map_err(|message| error(&message)));
+        }
+
+        if has_extension(path, "pbm") {
+            return fs::read(path)
+                .map_err(|io_error| error(&io_error))
+                .and_then(|data| pbm::parse(&data).map_err(|message| error(&message)));
+        }
+
+        if has_extension(path, "svg") {
+            #[cfg(feature = "svg")]
+            return svg::render(path, size, matches!(self.mask, Some(Mask::Alpha)))
+                .map_err(|message| error(&message));
+
+            #[cfg(not(feature = "svg"))]
+            {
+                let _ = size;
+                return Err(error(&"SVG images require the `svg` feature"));
+            }
+        }
+
+        let img = image::open(path).map_err(|image_error| error(&image_error))?;
+
+        Ok(img.to_rgba8())
+    }
+}
+
+/// Checks if `path`, or every path matched by it, has the given `extension`.
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|actual| actual.eq_ignore_ascii_case(extension))
+}
@@ -0,0 +1,144 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+use syn::{
+    parse::{Error, Result},
+    LitStr,
+};
+
+/// Base directory relative paths are resolved against.
+#[derive(Clone, Copy)]
+pub(crate) enum RelativeTo {
+    /// Directory of the source file the path was written in.
+    File,
+    /// Directory containing the `Cargo.toml` of the crate being compiled.
+    Manifest,
+}
+
+impl RelativeTo {
+    pub(crate) fn from_lit(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "file" => Ok(Self::File),
+            "manifest" => Ok(Self::Manifest),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "invalid `relative_to` value \"{}\", expected \"file\" or \"manifest\"",
+                    other
+                ),
+            )),
+        }
+    }
+
+    fn base_dir(self, lit: &LitStr) -> Option<PathBuf> {
+        match self {
+            Self::File => {
+                let file = lit.span().unwrap().local_file()?;
+                let dir = file.parent()?;
+                std::path::absolute(dir).ok()
+            }
+            Self::Manifest => env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from),
+        }
+    }
+}
+
+/// Resolves the path written in `lit` to an existing file.
+///
+/// Absolute paths are taken as they are. Relative paths are looked up in the base
+/// directory given by `relative_to`, or, if it's not provided, first relative to the
+/// invoking source file and then relative to the manifest directory.
+pub(crate) fn resolve(lit: &LitStr, relative_to: Option<RelativeTo>) -> Result<PathBuf> {
+    let path_str = lit.value();
+    let path = Path::new(&path_str);
+
+    let candidates = base_dirs(lit, relative_to)
+        .into_iter()
+        .map(|dir| dir.join(path))
+        .collect::<Vec<_>>();
+
+    if let Some(found) = candidates.iter().find(|candidate| candidate.is_file()) {
+        return Ok(found.clone());
+    }
+
+    Err(Error::new(
+        lit.span(),
+        format!(
+            "couldn't find {}, tried:{}",
+            path.display(),
+            list(&candidates)
+        ),
+    ))
+}
+
+/// Resolves the glob pattern written in `lit` to the existing files it matches, in
+/// alphabetical order.
+///
+/// The pattern is looked up the same way as a path in [`resolve`], and the first
+/// base directory in which it matches any files is used.
+pub(crate) fn glob(lit: &LitStr, relative_to: Option<RelativeTo>) -> Result<Vec<PathBuf>> {
+    let pattern = lit.value();
+    let dirs = base_dirs(lit, relative_to);
+
+    for dir in &dirs {
+        let escaped_dir = glob::Pattern::escape(&dir.to_string_lossy());
+        let candidate = Path::new(&escaped_dir).join(&pattern);
+        let paths = glob::glob(&candidate.to_string_lossy()).map_err(|error| {
+            Error::new(
+                lit.span(),
+                format!("invalid pattern \"{}\": {}", pattern, error),
+            )
+        })?;
+
+        let mut files = paths
+            .filter_map(|path| path.ok())
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        files.sort();
+
+        if !files.is_empty() {
+            return Ok(files);
+        }
+    }
+
+    let candidates = dirs
+        .iter()
+        .map(|dir| dir.join(&pattern))
+        .collect::<Vec<_>>();
+
+    Err(Error::new(
+        lit.span(),
+        format!("no files match {}, tried:{}", pattern, list(&candidates)),
+    ))
+}
+
+/// Directories the path written in `lit` can be relative to, in order of precedence.
+fn base_dirs(lit: &LitStr, relative_to: Option<RelativeTo>) -> Vec<PathBuf> {
+    if Path::new(&lit.value()).is_absolute() {
+        return vec![PathBuf::new()];
+    }
+
+    let bases = match relative_to {
+        Some(relative_to) => vec![relative_to],
+        None => vec![RelativeTo::File, RelativeTo::Manifest],
+    };
+    let mut dirs = bases
+        .into_iter()
+        .filter_map(|base| base.base_dir(lit))
+        .collect::<Vec<_>>();
+    dirs.dedup();
+
+    if dirs.is_empty() {
+        // Neither the invoking file nor the manifest directory are known, which can
+        // happen outside of Cargo; fall back to the current directory.
+        dirs.push(PathBuf::new());
+    }
+    dirs
+}
+
+fn list(candidates: &[PathBuf]) -> String {
+    candidates
+        .iter()
+        .map(|candidate| format!("\n  {}", candidate.display()))
+        .collect()
+}
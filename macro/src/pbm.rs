@@ -0,0 +1,94 @@
+use image::{Rgba, RgbaImage};
+
+/// Parses a PBM file, in either the plain (`P1`) or the raw (`P4`) format. Only the
+/// first image is read if the file has more than one.
+///
+/// Ones become black and zeros become white, as the format defines.
+pub(crate) fn parse(data: &[u8]) -> Result<RgbaImage, String> {
+    let raw = match data.get(..2) {
+        Some(b"P1") => false,
+        Some(b"P4") => true,
+        _ => return Err("expected a P1 or P4 magic number".into()),
+    };
+    let mut reader = Reader { data, pos: 2 };
+    let width = reader.integer().ok_or("invalid width")?;
+    let height = reader.integer().ok_or("invalid height")?;
+    let len = width.checked_mul(height).ok_or("image is too large")?;
+
+    let black = if raw {
+        // A single whitespace character separates the header from the pixel data
+        let pixels = &data[(reader.pos + 1).min(data.len())..];
+        let row_len = width.div_ceil(8);
+
+        if pixels.len() < row_len * height {
+            return Err("unexpected end of the pixel data".into());
+        }
+
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| pixels[y * row_len + x / 8] & (0b1000_0000 >> (x % 8)) != 0)
+            .collect::<Vec<_>>()
+    } else {
+        (0..len)
+            .map(|_| reader.bit())
+            .collect::<Option<Vec<_>>>()
+            .ok_or("invalid or missing pixel")?
+    };
+
+    Ok(RgbaImage::from_fn(width as u32, height as u32, |x, y| {
+        if black[y as usize * width + x as usize] {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    }))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    /// Skips whitespace and comments, which run from `#` to the end of the line.
+    fn skip(&mut self) {
+        while let Some(&byte) = self.data.get(self.pos) {
+            match byte {
+                b'#' => {
+                    while self.data.get(self.pos).is_some_and(|&byte| byte != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Option<usize> {
+        self.skip();
+        let start = self.pos;
+        while self.data.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.data[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Reads a single `0` or `1` of the plain format, which don't have to be
+    /// separated by whitespace.
+    fn bit(&mut self) -> Option<bool> {
+        self.skip();
+        let bit = match self.data.get(self.pos)? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.pos += 1;
+
+        Some(bit)
+    }
+}
@@ -0,0 +1,383 @@
+use crate::{bitmap::Bitmap, emit::PixelData, env_var_tracked_if_available};
+use proc_macro2::Span;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use syn::parse::{Error, Result};
+
+/// Stamp embedded by a macro, as listed in the report.
+struct Entry {
+    /// File, line and column of the macro invocation.
+    location: String,
+    /// File that the stamp was included from, if any.
+    source: Option<String>,
+    /// Which of the stamps of the invocation this is, e.g. the index of a tile, or
+    /// nothing if there's only one.
+    name: String,
+    width: usize,
+    height: usize,
+    masked: bool,
+    /// Bytes of pixel data and mask that the stamp adds to the binary, which is none
+    /// if it's identical to a stamp before it in the same invocation.
+    bytes: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Json,
+    Csv,
+}
+
+/// Stamps embedded so far while compiling the current crate.
+#[derive(Default)]
+struct Report {
+    entries: Vec<Entry>,
+    /// Number of the entries that have been written out already, after which new ones
+    /// are appended.
+    written: usize,
+    /// Whether any of the entries that have been written out was replaced since, so
+    /// that the whole report has to be written again.
+    stale: bool,
+}
+
+static REPORT: Mutex<Report> = Mutex::new(Report {
+    entries: Vec::new(),
+    written: 0,
+    stale: false,
+});
+
+/// End of the JSON report, before which new entries are inserted.
+const JSON_END: &str = "\n]\n";
+const CSV_HEADER: &str = "location,source,name,width,height,encoding,bytes\n";
+
+/// Adds the `bitmaps` embedded by the macro invoked at `span`, along with their names,
+/// to the report of the current crate, and writes it out, if the report is enabled.
+/// The bitmaps are appended to `pixel_data`, which must hold the bitmaps that the
+/// invocation pushes before them, to count only the bytes that each of them adds.
+///
+/// The report is enabled by setting the `STOCKBOOK_REPORT` environment variable to
+/// either `json` or `csv`, and is written to the directory given by the
+/// `STOCKBOOK_REPORT_DIR` environment variable, or to the `OUT_DIR` of the crate.
+/// Errors are reported at `span`.
+pub(crate) fn record<'a>(
+    span: Span,
+    source: Option<&Path>,
+    bitmaps: impl IntoIterator<Item = (String, &'a Bitmap)>,
+    pixel_data: &mut PixelData,
+) -> Result<()> {
+    let Some(format) = env_var_tracked_if_available("STOCKBOOK_REPORT") else {
+        return Ok(());
+    };
+    let format = match format.as_str() {
+        "json" => Format::Json,
+        "csv" => Format::Csv,
+        other => {
+            return Err(Error::new(
+                span,
+                format!(
+                    "invalid `STOCKBOOK_REPORT` value \"{}\", expected \"json\" or \"csv\"",
+                    other
+                ),
+            ))
+        }
+    };
+    let dir = env_var_tracked_if_available("STOCKBOOK_REPORT_DIR")
+        .or_else(|| env::var("OUT_DIR").ok())
+        .ok_or_else(|| {
+            Error::new(
+                span,
+                "`STOCKBOOK_REPORT` requires either `STOCKBOOK_REPORT_DIR` to be set, or a \
+                 build script, for `OUT_DIR`",
+            )
+        })?;
+
+    let location = span
+        .unwrap()
+        .local_file()
+        .map(|file| {
+            let span = span.unwrap();
+            format!("{}:{}:{}", relative(&file), span.line(), span.column())
+        })
+        .unwrap_or_default();
+    let source = source.map(relative);
+
+    let entries = bitmaps.into_iter().map(|(name, bitmap)| {
+        let len = pixel_data.len();
+        pixel_data.append(bitmap);
+
+        Entry {
+            location: location.clone(),
+            source: source.clone(),
+            name,
+            width: bitmap.width,
+            height: bitmap.height,
+            masked: bitmap.mask.is_some(),
+            bytes: pixel_data.len() - len,
+        }
+    });
+
+    let mut report = REPORT.lock().unwrap_or_else(|error| error.into_inner());
+    report.add(entries);
+
+    let crate_name = env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| "unknown".into());
+    let extension = match format {
+        Format::Json => "json",
+        Format::Csv => "csv",
+    };
+    let path = PathBuf::from(dir).join(format!("stockbook-report-{}.{}", crate_name, extension));
+
+    report.write(&path, format).map_err(|error| {
+        Error::new(
+            span,
+            format!("couldn't write {}: {}", path.display(), error),
+        )
+    })
+}
+
+impl Report {
+    /// Adds `entries`, replacing the ones of the same stamps.
+    fn add(&mut self, entries: impl IntoIterator<Item = Entry>) {
+        for entry in entries {
+            // The same invocation may be expanded more than once by a long-running
+            // process, like an IDE, in which case its entries are replaced
+            let existing = self.entries.iter().position(|other| {
+                !entry.location.is_empty()
+                    && other.location == entry.location
+                    && other.name == entry.name
+            });
+            match existing {
+                Some(index) => {
+                    self.entries[index] = entry;
+                    self.stale |= index < self.written;
+                }
+                None => self.entries.push(entry),
+            }
+        }
+    }
+
+    /// Writes the report out to `path`, only appending the new entries if the file
+    /// has been written before and none of its entries were replaced.
+    fn write(&mut self, path: &Path, format: Format) -> io::Result<()> {
+        if self.written == 0 || self.stale {
+            fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+            fs::write(path, document(format, &self.entries))?;
+        } else {
+            append(path, format, &self.entries[self.written..])?;
+        }
+        self.written = self.entries.len();
+        self.stale = false;
+
+        Ok(())
+    }
+}
+
+/// Whole report with the given `entries`.
+fn document(format: Format, entries: &[Entry]) -> String {
+    match format {
+        Format::Json => {
+            let objects = entries.iter().map(json).collect::<Vec<_>>();
+            format!("[\n{}{}", objects.join(",\n"), JSON_END)
+        }
+        Format::Csv => CSV_HEADER.to_owned() + &entries.iter().map(csv).collect::<String>(),
+    }
+}
+
+/// Adds `entries` to the end of the report at `path`, which must have been written by
+/// [`document`] before, so that every entry is written only once.
+fn append(path: &Path, format: Format, entries: &[Entry]) -> io::Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    match format {
+        Format::Json => {
+            let objects = entries.iter().map(json).collect::<Vec<_>>();
+            let mut file = OpenOptions::new().write(true).open(path)?;
+            file.seek(SeekFrom::End(-(JSON_END.len() as i64)))?;
+            write!(file, ",\n{}{}", objects.join(",\n"), JSON_END)
+        }
+        Format::Csv => {
+            let rows = entries.iter().map(csv).collect::<String>();
+            OpenOptions::new()
+                .append(true)
+                .open(path)?
+                .write_all(rows.as_bytes())
+        }
+    }
+}
+
+/// Path relative to the manifest directory of the crate, if it's within it.
+fn relative(path: &Path) -> String {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from);
+    let path = manifest_dir
+        .as_deref()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path);
+
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn encoding(entry: &Entry) -> &'static str {
+    if entry.masked {
+        "1-bit+mask"
+    } else {
+        "1-bit"
+    }
+}
+
+/// Object of a single entry in the JSON report, without a trailing comma.
+fn json(entry: &Entry) -> String {
+    let string = |value: &str| {
+        let mut escaped = String::from('"');
+        for char in value.chars() {
+            match char {
+                '"' => escaped += "\\\"",
+                '\\' => escaped += "\\\\",
+                char if char.is_control() => escaped += &format!("\\u{:04x}", char as u32),
+                char => escaped.push(char),
+            }
+        }
+        escaped.push('"');
+        escaped
+    };
+
+    format!(
+        "  {{\"location\": {}, \"source\": {}, \"name\": {}, \"width\": {}, \
+         \"height\": {}, \"encoding\": {}, \"bytes\": {}}}",
+        string(&entry.location),
+        entry.source.as_deref().map_or("null".into(), string),
+        string(&entry.name),
+        entry.width,
+        entry.height,
+        string(encoding(entry)),
+        entry.bytes,
+    )
+}
+
+/// Row of a single entry in the CSV report, with a trailing newline.
+fn csv(entry: &Entry) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_owned()
+        }
+    };
+
+    format!(
+        "{},{},{},{},{},{},{}\n",
+        field(&entry.location),
+        field(entry.source.as_deref().unwrap_or_default()),
+        field(&entry.name),
+        entry.width,
+        entry.height,
+        encoding(entry),
+        entry.bytes,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, source: Option<&str>, masked: bool, bytes: usize) -> Entry {
+        Entry {
+            location: "src/lib.rs:3:22".into(),
+            source: source.map(Into::into),
+            name: name.into(),
+            width: 8,
+            height: 8,
+            masked,
+            bytes,
+        }
+    }
+
+    /// Empty directory for the report of a single test.
+    fn report_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("stockbook-report-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_json_report() {
+        let dir = report_dir("json");
+        let path = dir.join("stockbook-report-test.json");
+        let mut report = Report::default();
+
+        report.add([entry("0", Some("assets/tiles.png"), false, 8)]);
+        report.write(&path, Format::Json).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[\n  \
+             {\"location\": \"src/lib.rs:3:22\", \"source\": \"assets/tiles.png\", \"name\": \"0\", \
+             \"width\": 8, \"height\": 8, \"encoding\": \"1-bit\", \"bytes\": 8}\n\
+             ]\n"
+        );
+
+        // Appended before the closing bracket
+        report.add([entry("\"1\"", None, true, 0)]);
+        report.write(&path, Format::Json).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[\n  \
+             {\"location\": \"src/lib.rs:3:22\", \"source\": \"assets/tiles.png\", \"name\": \"0\", \
+             \"width\": 8, \"height\": 8, \"encoding\": \"1-bit\", \"bytes\": 8},\n  \
+             {\"location\": \"src/lib.rs:3:22\", \"source\": null, \"name\": \"\\\"1\\\"\", \
+             \"width\": 8, \"height\": 8, \"encoding\": \"1-bit+mask\", \"bytes\": 0}\n\
+             ]\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let dir = report_dir("csv");
+        let path = dir.join("stockbook-report-test.csv");
+        let mut report = Report::default();
+
+        report.add([
+            entry("0", Some("assets/tiles.png"), false, 8),
+            entry("1", Some("assets/tiles.png"), false, 0),
+        ]);
+        report.write(&path, Format::Csv).unwrap();
+        report.add([entry("a, b", None, true, 16)]);
+        report.write(&path, Format::Csv).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "location,source,name,width,height,encoding,bytes\n\
+             src/lib.rs:3:22,assets/tiles.png,0,8,8,1-bit,8\n\
+             src/lib.rs:3:22,assets/tiles.png,1,8,8,1-bit,0\n\
+             src/lib.rs:3:22,,\"a, b\",8,8,1-bit+mask,16\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_replaced_entries_are_rewritten() {
+        let dir = report_dir("replaced");
+        let path = dir.join("stockbook-report-test.csv");
+        let mut report = Report::default();
+
+        report.add([entry("0", None, false, 8), entry("1", None, false, 8)]);
+        report.write(&path, Format::Csv).unwrap();
+        report.add([entry("0", None, false, 4)]);
+        report.write(&path, Format::Csv).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "location,source,name,width,height,encoding,bytes\n\
+             src/lib.rs:3:22,,0,8,8,1-bit,4\n\
+             src/lib.rs:3:22,,1,8,8,1-bit,8\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
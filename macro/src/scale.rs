@@ -0,0 +1,197 @@
+use image::{Rgba, RgbaImage};
+use syn::{
+    parse::{Error, Result},
+    LitStr,
+};
+
+/// Algorithm used to upscale an image by an integer factor.
+#[derive(Clone, Copy, Default)]
+pub(crate) enum Scaler {
+    /// Every pixel becomes a square of pixels of the same color.
+    #[default]
+    Nearest,
+    /// Eric's Pixel Expansion, also known as Scale2x, repeated for factors that are
+    /// higher powers of two.
+    Epx,
+    /// Scale3x, repeated for factors that are higher powers of three.
+    Scale3x,
+}
+
+impl Scaler {
+    pub(crate) fn from_lit(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "nearest" => Ok(Self::Nearest),
+            "epx" | "scale2x" => Ok(Self::Epx),
+            "scale3x" => Ok(Self::Scale3x),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "invalid `scaler` value \"{}\", expected one of \"nearest\", \"epx\", \
+                     \"scale2x\" or \"scale3x\"",
+                    other
+                ),
+            )),
+        }
+    }
+
+    /// Checks if the scaler can upscale by `factor`.
+    pub(crate) fn supports(self, factor: u32) -> bool {
+        match self {
+            Self::Nearest => factor > 0,
+            Self::Epx => factor.is_power_of_two(),
+            Self::Scale3x => is_power_of(factor, 3),
+        }
+    }
+
+    /// Upscales `img` by `factor`, which must be [supported](Scaler::supports).
+    pub(crate) fn apply(self, img: RgbaImage, factor: u32) -> RgbaImage {
+        match self {
+            Self::Nearest => nearest(&img, factor),
+            Self::Epx => repeat(img, factor, 2, epx),
+            Self::Scale3x => repeat(img, factor, 3, scale3x),
+        }
+    }
+}
+
+fn is_power_of(mut n: u32, base: u32) -> bool {
+    while n > 1 && n.is_multiple_of(base) {
+        n /= base;
+    }
+    n == 1
+}
+
+fn repeat(
+    mut img: RgbaImage,
+    mut factor: u32,
+    step: u32,
+    scaler: fn(&RgbaImage) -> RgbaImage,
+) -> RgbaImage {
+    while factor > 1 {
+        img = scaler(&img);
+        factor /= step;
+    }
+    img
+}
+
+fn nearest(img: &RgbaImage, factor: u32) -> RgbaImage {
+    RgbaImage::from_fn(img.width() * factor, img.height() * factor, |x, y| {
+        *img.get_pixel(x / factor, y / factor)
+    })
+}
+
+/// Neighbourhood of a pixel, with pixels beyond the edges of the image replaced by
+/// the nearest pixel within them.
+///
+/// ```text
+/// a b c
+/// d e f
+/// g h i
+/// ```
+struct Neighbourhood {
+    a: Rgba<u8>,
+    b: Rgba<u8>,
+    c: Rgba<u8>,
+    d: Rgba<u8>,
+    e: Rgba<u8>,
+    f: Rgba<u8>,
+    g: Rgba<u8>,
+    h: Rgba<u8>,
+    i: Rgba<u8>,
+}
+
+impl Neighbourhood {
+    fn new(img: &RgbaImage, x: u32, y: u32) -> Self {
+        let at = |dx: i64, dy: i64| {
+            let x = (i64::from(x) + dx).clamp(0, i64::from(img.width()) - 1);
+            let y = (i64::from(y) + dy).clamp(0, i64::from(img.height()) - 1);
+            *img.get_pixel(x as u32, y as u32)
+        };
+
+        Self {
+            a: at(-1, -1),
+            b: at(0, -1),
+            c: at(1, -1),
+            d: at(-1, 0),
+            e: at(0, 0),
+            f: at(1, 0),
+            g: at(-1, 1),
+            h: at(0, 1),
+            i: at(1, 1),
+        }
+    }
+}
+
+fn epx(img: &RgbaImage) -> RgbaImage {
+    let mut out = RgbaImage::new(img.width() * 2, img.height() * 2);
+
+    for (x, y, _) in img.enumerate_pixels() {
+        let Neighbourhood { b, d, e, f, h, .. } = Neighbourhood::new(img, x, y);
+
+        let pixels = [
+            if d == b && b != f && d != h { d } else { e },
+            if b == f && b != d && f != h { f } else { e },
+            if d == h && d != b && h != f { d } else { e },
+            if h == f && d != h && b != f { f } else { e },
+        ];
+
+        for (index, pixel) in pixels.into_iter().enumerate() {
+            let index = index as u32;
+            out.put_pixel(x * 2 + index % 2, y * 2 + index / 2, pixel);
+        }
+    }
+
+    out
+}
+
+fn scale3x(img: &RgbaImage) -> RgbaImage {
+    let mut out = RgbaImage::new(img.width() * 3, img.height() * 3);
+
+    for (x, y, _) in img.enumerate_pixels() {
+        let Neighbourhood {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+            h,
+            i,
+        } = Neighbourhood::new(img, x, y);
+
+        let pixels = [
+            if d == b && b != f && d != h { d } else { e },
+            if (d == b && b != f && d != h && e != c) || (b == f && b != d && f != h && e != a) {
+                b
+            } else {
+                e
+            },
+            if b == f && b != d && f != h { f } else { e },
+            if (d == b && b != f && d != h && e != g) || (d == h && d != b && h != f && e != a) {
+                d
+            } else {
+                e
+            },
+            e,
+            if (b == f && b != d && f != h && e != i) || (h == f && d != h && b != f && e != c) {
+                f
+            } else {
+                e
+            },
+            if d == h && d != b && h != f { d } else { e },
+            if (d == h && d != b && h != f && e != i) || (h == f && d != h && b != f && e != g) {
+                h
+            } else {
+                e
+            },
+            if h == f && d != h && b != f { f } else { e },
+        ];
+
+        for (index, pixel) in pixels.into_iter().enumerate() {
+            let index = index as u32;
+            out.put_pixel(x * 3 + index % 3, y * 3 + index / 3, pixel);
+        }
+    }
+
+    out
+}
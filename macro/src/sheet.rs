@@ -0,0 +1,188 @@
+use crate::{
+    args::{Args, Spanned},
+    bitmap::Bitmap,
+    emit::PixelData,
+    options::Options,
+    report,
+};
+use image::{imageops, RgbaImage};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `stamp_sheet!` macro.
+pub(crate) struct Sheet {
+    tiles: Vec<Bitmap>,
+}
+
+/// Grid of equally sized tiles in an image, with optional space around and between
+/// them.
+pub(crate) struct Layout {
+    tile: [u32; 2],
+    margin: u32,
+    spacing: u32,
+}
+
+impl Layout {
+    /// Parses the layout arguments, where `tile` is the name of the argument with the
+    /// size of a tile.
+    pub(crate) fn from_args(args: &mut Args, tile: &str, span: Span) -> Result<Self> {
+        let size = args.tuple::<u32, 2>(tile)?;
+        let margin = args.int::<u32>("margin")?.map_or(0, |margin| margin.value);
+        let spacing = args
+            .int::<u32>("spacing")?
+            .map_or(0, |spacing| spacing.value);
+
+        let Some(Spanned {
+            value: [width, height],
+            span: size_span,
+        }) = size
+        else {
+            return Err(Error::new(
+                span,
+                format!("missing the {} size, e.g. `{} = (8, 8)`", tile, tile),
+            ));
+        };
+        if width == 0 || height == 0 {
+            return Err(Error::new(
+                size_span,
+                format!("`{}` must not be zero", tile),
+            ));
+        }
+
+        Ok(Self {
+            tile: [width, height],
+            margin,
+            spacing,
+        })
+    }
+
+    /// Number of columns and rows of tiles that fit in `img`.
+    pub(crate) fn grid(&self, img: &RgbaImage) -> [u32; 2] {
+        let tiles_along = |length: u32, tile_length: u32| {
+            (length.saturating_sub(self.margin) + self.spacing) / (tile_length + self.spacing)
+        };
+
+        [
+            tiles_along(img.width(), self.tile[0]),
+            tiles_along(img.height(), self.tile[1]),
+        ]
+    }
+
+    /// Top-left corners of the tiles that fit in `img`, in row-major order. Fails if
+    /// there are none.
+    pub(crate) fn positions(&self, img: &RgbaImage, span: Span) -> Result<Vec<(u32, u32)>> {
+        let [tile_width, tile_height] = self.tile;
+        let [columns, rows] = self.grid(img);
+
+        let positions = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                (
+                    self.margin + column * (tile_width + self.spacing),
+                    self.margin + row * (tile_height + self.spacing),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if positions.is_empty() {
+            return Err(Error::new(
+                span,
+                format!(
+                    "no {}x{} tiles fit in the {}x{} image",
+                    tile_width,
+                    tile_height,
+                    img.width(),
+                    img.height()
+                ),
+            ));
+        }
+
+        Ok(positions)
+    }
+
+    /// Copies the tile whose top-left corner is at `position` out of `img`.
+    pub(crate) fn crop(&self, img: &RgbaImage, (x, y): (u32, u32)) -> RgbaImage {
+        let [width, height] = self.tile;
+        imageops::crop_imm(img, x, y, width, height).to_image()
+    }
+}
+
+impl Parse for Sheet {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let layout = Layout::from_args(&mut args, "tile", input.span())?;
+        let count = args.int::<usize>("count")?;
+        let options = Options::from_args(&mut args, &lit_str)?;
+        args.finish()?;
+
+        let path = options.resolve(&lit_str)?;
+        let img = options.read_sheet(&path, lit_str.span())?;
+        let mut positions = layout.positions(&img, lit_str.span())?;
+
+        if let Some(Spanned { value: count, span }) = count {
+            if count > positions.len() {
+                let [columns, rows] = layout.grid(&img);
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "the image only has {} tiles ({} columns and {} rows)",
+                        positions.len(),
+                        columns,
+                        rows
+                    ),
+                ));
+            }
+            positions.truncate(count);
+        }
+
+        let tiles = positions
+            .into_iter()
+            .enumerate()
+            .map(|(index, (x, y))| {
+                let tile = layout.crop(&img, (x, y));
+                options.bitmap(tile, lit_str.span()).map_err(|error| {
+                    Error::new(
+                        error.span(),
+                        format!("in tile {} at {},{}: {}", index, x, y, error),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        report::record(
+            lit_str.span(),
+            Some(&path),
+            tiles
+                .iter()
+                .enumerate()
+                .map(|(index, tile)| (index.to_string(), tile)),
+            &mut PixelData::default(),
+        )?;
+
+        Ok(Self { tiles })
+    }
+}
+
+impl ToTokens for Sheet {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut pixel_data = PixelData::default();
+        let stamps = self
+            .tiles
+            .iter()
+            .map(|tile| pixel_data.push(tile))
+            .collect::<Vec<_>>();
+        let items = pixel_data.items();
+
+        tokens.extend(quote! {
+            {
+                #items
+
+                unsafe { [#(#stamps),*] }
+            }
+        });
+    }
+}
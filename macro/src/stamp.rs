@@ -0,0 +1,88 @@
+use crate::{
+    args::Args,
+    bitmap::Bitmap,
+    emit::{self, PixelData, StampKind},
+    options::Options,
+    report,
+};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `stamp!` macro.
+pub(crate) struct Stamp {
+    /// Errors past parsing are kept, so that a placeholder can be emitted in place of
+    /// the stamp.
+    bitmap: std::result::Result<Bitmap, Error>,
+    kind: StampKind,
+}
+
+impl Parse for Stamp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let args = input.parse::<Args>()?;
+        let kind = if args.has("sized") {
+            StampKind::Sized
+        } else if args.has("transparent") {
+            StampKind::Masked
+        } else {
+            StampKind::Plain
+        };
+        let bitmap = load(&lit_str, args);
+
+        Ok(Self { bitmap, kind })
+    }
+}
+
+impl ToTokens for Stamp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let bitmap = match &self.bitmap {
+            Ok(bitmap) => bitmap,
+            Err(error) => return tokens.extend(emit::placeholder(error, self.kind)),
+        };
+
+        let mut pixel_data = PixelData::default();
+        let stamp = match self.kind {
+            StampKind::Sized => pixel_data.push_sized(bitmap),
+            _ => pixel_data.push(bitmap),
+        };
+        let items = pixel_data.items();
+
+        tokens.extend(quote! {
+            {
+                #items
+
+                unsafe { #stamp }
+            }
+        });
+    }
+}
+
+/// Includes the image at the path written in `lit_str` with the given arguments.
+fn load(lit_str: &LitStr, mut args: Args) -> Result<Bitmap> {
+    if let (Some(span), true) = (args.span("sized"), args.has("transparent")) {
+        return Err(Error::new(
+            span,
+            "`sized` can't be used together with `transparent`",
+        ));
+    }
+    args.flag("sized")?;
+
+    let options = Options::from_args(&mut args, lit_str)?;
+    args.finish()?;
+
+    let path = options.resolve(lit_str)?;
+    let img = options.read(&path, lit_str.span())?;
+    let bitmap = options.bitmap(img, lit_str.span())?;
+    report::record(
+        lit_str.span(),
+        Some(&path),
+        [(String::new(), &bitmap)],
+        &mut PixelData::default(),
+    )?;
+
+    Ok(bitmap)
+}
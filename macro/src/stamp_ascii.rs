@@ -0,0 +1,186 @@
+use crate::{
+    args::Args,
+    bitmap::Bitmap,
+    emit::PixelData,
+    path::{self, RelativeTo},
+    report, track_file_if_available,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::{fs, ops::Range, path::Path};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `stamp_ascii!` macro.
+pub(crate) struct StampAscii {
+    bitmap: Bitmap,
+}
+
+/// Error in ASCII art, along with the range of the source it refers to.
+struct ArtError {
+    range: Range<usize>,
+    message: String,
+}
+
+impl Parse for StampAscii {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let relative_to_lit = args.string("relative_to")?;
+        args.finish()?;
+
+        let value = lit_str.value();
+
+        let (bitmap, source) = if Path::new(&value)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("txt"))
+        {
+            let relative_to = relative_to_lit
+                .map(|lit| RelativeTo::from_lit(&lit))
+                .transpose()?;
+            let path = path::resolve(&lit_str, relative_to)?;
+            track_file_if_available(&path);
+
+            let source = fs::read_to_string(&path).map_err(|error| {
+                Error::new(
+                    lit_str.span(),
+                    format!("couldn't read {}: {}", path.display(), error),
+                )
+            })?;
+
+            let bitmap = parse_art(&source).map_err(|error| {
+                Error::new(
+                    lit_str.span(),
+                    format!("in {}: {}", path.display(), error.message),
+                )
+            })?;
+
+            (bitmap, Some(path))
+        } else {
+            if let Some(lit) = relative_to_lit {
+                return Err(Error::new(
+                    lit.span(),
+                    "`relative_to` only applies to `.txt` files",
+                ));
+            }
+
+            let bitmap = parse_art(&value)
+                .map_err(|error| Error::new(subspan(&lit_str, error.range), error.message))?;
+
+            (bitmap, None)
+        };
+        report::record(
+            lit_str.span(),
+            source.as_deref(),
+            [(String::new(), &bitmap)],
+            &mut PixelData::default(),
+        )?;
+
+        Ok(Self { bitmap })
+    }
+}
+
+impl ToTokens for StampAscii {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut pixel_data = PixelData::default();
+        let stamp = pixel_data.push(&self.bitmap);
+        let items = pixel_data.items();
+
+        tokens.extend(quote! {
+            {
+                #items
+
+                unsafe { #stamp }
+            }
+        });
+    }
+}
+
+/// Parses rows of `#` (white) and `.` (black) characters. Whitespace around the rows
+/// is ignored, and so are blank lines before the first row and after the last one.
+fn parse_art(source: &str) -> std::result::Result<Bitmap, ArtError> {
+    let mut rows = Vec::<(usize, Range<usize>)>::new();
+    let mut offset = 0;
+
+    for (index, line) in source.split('\n').enumerate() {
+        let start = offset + (line.len() - line.trim_start().len());
+        let end = offset + line.trim_end().len();
+        offset += line.len() + 1;
+
+        rows.push((index + 1, start..end.max(start)));
+    }
+
+    let first = rows.iter().position(|(_, range)| !range.is_empty());
+    let last = rows.iter().rposition(|(_, range)| !range.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return Err(ArtError {
+            range: 0..source.len(),
+            message: "expected at least one row of `#` and `.` characters".into(),
+        });
+    };
+    let rows = &rows[first..=last];
+
+    let width = source[rows[0].1.clone()].chars().count();
+    let mut bits = Vec::new();
+
+    for (line, range) in rows {
+        let row = &source[range.clone()];
+
+        for (column, (index, char)) in row.char_indices().enumerate() {
+            bits.push(match char {
+                '#' => true,
+                '.' => false,
+                other => {
+                    return Err(ArtError {
+                        range: range.start + index..range.start + index + other.len_utf8(),
+                        message: format!(
+                            "unexpected character {:?} at line {}, column {}, expected `#` or `.`",
+                            other,
+                            line,
+                            column + 1,
+                        ),
+                    })
+                }
+            });
+        }
+
+        let row_width = row.chars().count();
+        if row_width != width {
+            return Err(ArtError {
+                range: range.clone(),
+                message: format!(
+                    "row at line {} is {} pixels wide, but the first row is {} pixels wide",
+                    line, row_width, width,
+                ),
+            });
+        }
+    }
+
+    Ok(Bitmap {
+        width,
+        height: rows.len(),
+        data: Bitmap::encode(bits.into_iter()),
+        mask: None,
+    })
+}
+
+/// Span of the given `range` of the value of `lit`, if the compiler can tell it and
+/// the value is written out verbatim, or the span of the whole literal otherwise.
+fn subspan(lit: &LitStr, range: Range<usize>) -> Span {
+    let token = lit.token();
+    let source = token.to_string();
+    let value = lit.value();
+
+    // Skip the `r#"` of raw strings, or just the `"` of regular ones
+    let prefix = source.find('"').map_or(0, |quote| quote + 1);
+    let verbatim = source
+        .get(prefix..prefix + value.len())
+        .is_some_and(|written| written == value);
+
+    verbatim
+        .then(|| token.subspan(prefix + range.start..prefix + range.end))
+        .flatten()
+        .unwrap_or_else(|| lit.span())
+}
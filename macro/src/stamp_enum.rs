@@ -0,0 +1,171 @@
+use crate::{args::Args, bitmap::Bitmap, emit::PixelData, options::Options, report};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::path::PathBuf;
+use syn::{
+    braced, parenthesized,
+    parse::{Error, Parse, ParseStream, Result},
+    punctuated::Punctuated,
+    Attribute, Ident, LitStr, Token, Visibility,
+};
+
+/// Input and output of the `stamp_enum!` macro.
+pub(crate) struct StampEnum {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    variants: Vec<Variant>,
+}
+
+/// Variant of the enum, e.g. `Home = "home.png"` or
+/// `Home = ("home.png", threshold = 128)`.
+struct Variant {
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    /// Span of the path of the image, at which the variant is reported.
+    span: Span,
+    path: PathBuf,
+    bitmap: Bitmap,
+}
+
+impl Parse for StampEnum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse::<Visibility>()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse::<Ident>()?;
+
+        let content;
+        let brace_token = braced!(content in input);
+        let variants = Punctuated::<Variant, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let Some(first) = variants.first() else {
+            return Err(Error::new(
+                brace_token.span,
+                "expected at least one variant, e.g. `Home = \"home.png\"`",
+            ));
+        };
+        let masked = first.bitmap.mask.is_some();
+        if let Some(variant) = variants
+            .iter()
+            .find(|variant| variant.bitmap.mask.is_some() != masked)
+        {
+            return Err(Error::new(
+                variant.ident.span(),
+                "either all or none of the variants must be `transparent`",
+            ));
+        }
+
+        let mut pixel_data = PixelData::default();
+        for variant in &variants {
+            report::record(
+                variant.span,
+                Some(&variant.path),
+                [(variant.ident.to_string(), &variant.bitmap)],
+                &mut pixel_data,
+            )?;
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            variants,
+        })
+    }
+}
+
+impl Parse for Variant {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let ident = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+
+        let (lit_str, mut args) = if input.peek(LitStr) {
+            (input.parse::<LitStr>()?, Args::default())
+        } else {
+            let content;
+            parenthesized!(content in input);
+            (content.parse::<LitStr>()?, content.parse::<Args>()?)
+        };
+        let options = Options::from_args(&mut args, &lit_str)?;
+        args.finish()?;
+
+        let path = options.resolve(&lit_str)?;
+        let img = options.read(&path, lit_str.span())?;
+        let bitmap = options.bitmap(img, lit_str.span())?;
+
+        Ok(Self {
+            attrs,
+            ident,
+            span: lit_str.span(),
+            path,
+            bitmap,
+        })
+    }
+}
+
+impl ToTokens for StampEnum {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let attrs = &self.attrs;
+        let vis = &self.vis;
+        let ident = &self.ident;
+        let variant_attrs = self.variants.iter().map(|variant| &variant.attrs);
+        let variants = self
+            .variants
+            .iter()
+            .map(|variant| &variant.ident)
+            .collect::<Vec<_>>();
+        let names = variants.iter().map(|variant| variant.to_string());
+        let variant_count = self.variants.len();
+
+        let mut pixel_data = PixelData::default();
+        let stamps = self
+            .variants
+            .iter()
+            .map(|variant| pixel_data.push(&variant.bitmap))
+            .collect::<Vec<_>>();
+        let items = pixel_data.items();
+        let stamp_type = pixel_data.stamp_type();
+
+        tokens.extend(quote! {
+            #(#attrs)*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #vis enum #ident {
+                #(
+                    #(#variant_attrs)*
+                    #variants,
+                )*
+            }
+
+            impl #ident {
+                /// Every variant, in the order of declaration.
+                pub const ALL: [Self; #variant_count] = [#(Self::#variants),*];
+
+                /// Stamp included for the variant.
+                pub fn stamp(&self) -> &'static #stamp_type {
+                    #items
+
+                    static STAMPS: [#stamp_type; #variant_count] = unsafe { [#(#stamps),*] };
+
+                    &STAMPS[*self as usize]
+                }
+
+                /// Name of the variant.
+                pub fn name(&self) -> &'static str {
+                    match self {
+                        #(Self::#variants => #names,)*
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(self.name())
+                }
+            }
+        });
+    }
+}
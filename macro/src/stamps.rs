@@ -0,0 +1,193 @@
+use crate::{args::Args, bitmap::Bitmap, emit::PixelData, options::Options, report};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::path::Path;
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    Attribute, Ident, LitStr, Token, Visibility,
+};
+
+/// Input and output of the `stamps!` macro.
+pub(crate) struct Stamps {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    stamps: Vec<NamedStamp>,
+}
+
+struct NamedStamp {
+    name: Ident,
+    file_name: String,
+    bitmap: Bitmap,
+}
+
+impl Parse for Stamps {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse::<Visibility>()?;
+        input.parse::<Token![mod]>()?;
+        let ident = input.parse::<Ident>()?;
+        input.parse::<Token![,]>()?;
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let options = Options::from_args(&mut args, &lit_str)?;
+        args.finish()?;
+
+        let mut stamps = Vec::<NamedStamp>::new();
+
+        for path in options.glob(&lit_str)? {
+            let (name, file_name) = name(&path, &stamps, lit_str.span())?;
+            let img = options.read(&path, lit_str.span())?;
+            let bitmap = options.bitmap(img, lit_str.span()).map_err(|error| {
+                Error::new(error.span(), format!("in {}: {}", file_name, error))
+            })?;
+            report::record(
+                lit_str.span(),
+                Some(&path),
+                [(name.to_string(), &bitmap)],
+                &mut PixelData::default(),
+            )?;
+
+            stamps.push(NamedStamp {
+                name,
+                file_name,
+                bitmap,
+            });
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            stamps,
+        })
+    }
+}
+
+/// Names the stamp included from `path` after the file stem, in uppercase. Fails if
+/// the name isn't a valid identifier, or if one of `stamps` already has it. Errors are
+/// reported at `span`.
+fn name(path: &Path, stamps: &[NamedStamp], span: Span) -> Result<(Ident, String)> {
+    let file_name = path
+        .file_name()
+        .map_or_else(Default::default, |name| name.to_string_lossy().into_owned());
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = syn::parse_str::<Ident>(&stem.to_uppercase()).map_err(|_| {
+        Error::new(
+            span,
+            format!(
+                "can't name a stamp after {}, `{}` isn't a valid identifier",
+                file_name,
+                stem.to_uppercase()
+            ),
+        )
+    })?;
+
+    if let Some(other) = stamps.iter().find(|stamp| stamp.name == name) {
+        return Err(Error::new(
+            span,
+            format!(
+                "both {} and {} would be named `{}`",
+                other.file_name, file_name, name
+            ),
+        ));
+    }
+
+    Ok((name, file_name))
+}
+
+impl ToTokens for Stamps {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let attrs = &self.attrs;
+        let vis = &self.vis;
+        let ident = &self.ident;
+
+        let stamps = self.stamps.iter().map(|stamp| {
+            let mut pixel_data = PixelData::default();
+            let expr = pixel_data.push(&stamp.bitmap);
+            let items = pixel_data.items();
+            let stamp_type = pixel_data.stamp_type();
+            let name = &stamp.name;
+            let doc = format!("Included from `{}`.", stamp.file_name);
+
+            quote! {
+                #[doc = #doc]
+                pub static #name: #stamp_type = {
+                    #items
+
+                    unsafe { #expr }
+                };
+            }
+        });
+
+        tokens.extend(quote! {
+            #(#attrs)*
+            #vis mod #ident {
+                #(#stamps)*
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_stamp(name: &str, file_name: &str) -> NamedStamp {
+        NamedStamp {
+            name: syn::parse_str(name).unwrap(),
+            file_name: file_name.to_owned(),
+            bitmap: Bitmap {
+                width: 0,
+                height: 0,
+                data: Vec::new(),
+                mask: None,
+            },
+        }
+    }
+
+    fn message(result: Result<(Ident, String)>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_name_after_file_stem() {
+        let (name, file_name) = name(
+            Path::new("assets/icons/arrow_left.png"),
+            &[named_stamp("ARROW_RIGHT", "arrow_right.png")],
+            Span::call_site(),
+        )
+        .unwrap();
+
+        assert_eq!(name.to_string(), "ARROW_LEFT");
+        assert_eq!(file_name, "arrow_left.png");
+    }
+
+    #[test]
+    fn test_invalid_identifier() {
+        assert_eq!(
+            message(name(
+                Path::new("icons/arrow-left.png"),
+                &[],
+                Span::call_site()
+            )),
+            "can't name a stamp after arrow-left.png, `ARROW-LEFT` isn't a valid identifier"
+        );
+        assert_eq!(
+            message(name(Path::new("icons/1up.png"), &[], Span::call_site())),
+            "can't name a stamp after 1up.png, `1UP` isn't a valid identifier"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_name() {
+        assert_eq!(
+            message(name(
+                Path::new("icons/Arrow.png"),
+                &[named_stamp("ARROW", "arrow.png")],
+                Span::call_site()
+            )),
+            "both arrow.png and Arrow.png would be named `ARROW`"
+        );
+    }
+}
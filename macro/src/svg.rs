@@ -0,0 +1,43 @@
+use image::{Rgba, RgbaImage};
+use resvg::{tiny_skia, usvg};
+use std::{fs, path::Path};
+
+/// Renders the SVG image at `path` at `size`, or at its own size if there's none.
+///
+/// Unless the image is to be `transparent`, it's composited onto a white background,
+/// since its own background usually is transparent black, which would otherwise be
+/// indistinguishable from its black shapes.
+pub(crate) fn render(
+    path: &Path,
+    size: Option<[u32; 2]>,
+    transparent: bool,
+) -> Result<RgbaImage, String> {
+    let data = fs::read(path).map_err(|error| error.to_string())?;
+    let options = usvg::Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_data(&data, &options).map_err(|error| error.to_string())?;
+
+    let [width, height] = size.unwrap_or_else(|| {
+        let size = tree.size().to_int_size();
+        [size.width(), size.height()]
+    });
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| format!("can't render an image of size {}x{}", width, height))?;
+
+    if !transparent {
+        pixmap.fill(tiny_skia::Color::WHITE);
+    }
+
+    let transform = tiny_skia::Transform::from_scale(
+        width as f32 / tree.size().width(),
+        height as f32 / tree.size().height(),
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    Ok(RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = pixmap.pixels()[(y * width + x) as usize].demultiply();
+        Rgba([pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()])
+    }))
+}
@@ -0,0 +1,256 @@
+#[cfg(feature = "ttf")]
+use crate::ttf::TtfArgs;
+use crate::{
+    args::{Args, Spanned},
+    bdf::BdfArgs,
+    bitmap::Bitmap,
+    emit::PixelData,
+    font::Font,
+    glyph_sheet::GlyphSheetArgs,
+    report,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::{ffi::OsStr, path::Path};
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `text_stamp!` macro.
+pub(crate) struct TextStamp {
+    bitmap: Bitmap,
+}
+
+/// Arguments of the macro that the font is included with, chosen by the extension of
+/// its path.
+enum FontSource {
+    GlyphSheet(GlyphSheetArgs),
+    Bdf(BdfArgs),
+    #[cfg(feature = "ttf")]
+    Ttf(TtfArgs),
+}
+
+/// Horizontal alignment of the lines of text.
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Parse for TextStamp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let text = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let font_lit = args.string("font")?.ok_or_else(|| {
+            Error::new(
+                input.span(),
+                "missing the path to the font, e.g. `font = \"font.bdf\"`",
+            )
+        })?;
+        let align = args
+            .string("align")?
+            .map(|lit| Align::from_lit(&lit))
+            .transpose()?
+            .unwrap_or(Align::Left);
+        let max_width = args.int::<usize>("max_width")?;
+        let source = FontSource::from_args(&font_lit, &mut args, input.span())?;
+        args.finish()?;
+
+        let font = source.load(&font_lit)?;
+
+        if let Some(missing) = text
+            .value()
+            .chars()
+            .find(|&c| c != '\n' && font.glyph(c).is_none())
+        {
+            return Err(Error::new(
+                text.span(),
+                format!("the font has no glyph for {:?}", missing),
+            ));
+        }
+
+        let lines = wrap(&font, &text.value(), max_width)?;
+        let bitmap = render(&font, &lines, align);
+        report::record(
+            text.span(),
+            Some(font.source()),
+            [(text.value(), &bitmap)],
+            &mut PixelData::default(),
+        )?;
+
+        Ok(Self { bitmap })
+    }
+}
+
+impl ToTokens for TextStamp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut pixel_data = PixelData::default();
+        let stamp = pixel_data.push(&self.bitmap);
+        let items = pixel_data.items();
+
+        tokens.extend(quote! {
+            {
+                #items
+
+                unsafe { #stamp }
+            }
+        });
+    }
+}
+
+impl FontSource {
+    /// Takes the arguments of the font at the path written in `lit`, reporting errors
+    /// that aren't specific to any of them at `span`.
+    fn from_args(lit: &LitStr, args: &mut Args, span: Span) -> Result<Self> {
+        let path = lit.value();
+        let extension = Path::new(&path)
+            .extension()
+            .and_then(OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("bdf") => Ok(Self::Bdf(BdfArgs::from_args(args)?)),
+            #[cfg(feature = "ttf")]
+            Some("ttf" | "otf") => Ok(Self::Ttf(TtfArgs::from_args(args, span)?)),
+            #[cfg(not(feature = "ttf"))]
+            Some("ttf" | "otf") => Err(Error::new(
+                lit.span(),
+                "TrueType and OpenType fonts require the `ttf` feature",
+            )),
+            _ => Ok(Self::GlyphSheet(GlyphSheetArgs::from_args(
+                args, lit, span,
+            )?)),
+        }
+    }
+
+    fn load(self, lit: &LitStr) -> Result<Font> {
+        match self {
+            Self::GlyphSheet(args) => args.load(lit),
+            Self::Bdf(args) => args.load(lit),
+            #[cfg(feature = "ttf")]
+            Self::Ttf(args) => args.load(lit),
+        }
+    }
+}
+
+impl Align {
+    fn from_lit(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "left" => Ok(Self::Left),
+            "center" => Ok(Self::Center),
+            "right" => Ok(Self::Right),
+            other => Err(Error::new(
+                lit.span(),
+                format!(
+                    "invalid `align` value \"{}\", expected one of \"left\", \"center\" \
+                     or \"right\"",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+/// Sum of the advances of the glyphs of `line`.
+fn width(font: &Font, line: &str) -> usize {
+    line.chars()
+        .filter_map(|c| font.glyph(c))
+        .map(|glyph| glyph.advance)
+        .sum()
+}
+
+/// Splits `text` into lines at line breaks and, if there's a `max_width`, between
+/// words, so that no line is wider than it.
+fn wrap(font: &Font, text: &str, max_width: Option<Spanned<usize>>) -> Result<Vec<String>> {
+    let Some(Spanned {
+        value: max_width,
+        span,
+    }) = max_width
+    else {
+        return Ok(text.split('\n').map(String::from).collect());
+    };
+
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+
+        for word in paragraph.split(' ') {
+            let word_width = width(font, word);
+            if word_width > max_width {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "\"{}\" is {} pixels wide, which doesn't fit in `max_width`",
+                        word, word_width
+                    ),
+                ));
+            }
+
+            if line.is_empty() {
+                line = word.to_string();
+            } else if width(font, &line) + width(font, " ") + word_width <= max_width {
+                line.push(' ');
+                line.push_str(word);
+            } else {
+                lines.push(line);
+                line = word.to_string();
+            }
+        }
+
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+/// Draws the `lines` one below another, in a bitmap just wide enough for the widest
+/// of them.
+fn render(font: &Font, lines: &[String], align: Align) -> Bitmap {
+    let line_height = font.metrics().line_height;
+    let widths = lines
+        .iter()
+        .map(|line| width(font, line))
+        .collect::<Vec<_>>();
+    let width = widths.iter().copied().max().unwrap_or(0);
+    let height = lines.len() * line_height;
+    let mut bits = vec![false; width * height];
+
+    for (index, (line, line_width)) in lines.iter().zip(widths).enumerate() {
+        let mut pen_x = match align {
+            Align::Left => 0,
+            Align::Center => (width - line_width) / 2,
+            Align::Right => width - line_width,
+        };
+        let pen_y = index * line_height;
+
+        for glyph in line.chars().filter_map(|c| font.glyph(c)) {
+            let [offset_x, offset_y] = glyph.offset;
+            let bitmap = &glyph.bitmap;
+
+            for (glyph_x, glyph_y) in
+                (0..bitmap.height).flat_map(|y| (0..bitmap.width).map(move |x| (x, y)))
+            {
+                if !bitmap.is_white(glyph_x, glyph_y) {
+                    continue;
+                }
+
+                let x = pen_x as isize + offset_x + glyph_x as isize;
+                let y = pen_y as isize + offset_y + glyph_y as isize;
+                if (0..width as isize).contains(&x) && (0..height as isize).contains(&y) {
+                    bits[y as usize * width + x as usize] = true;
+                }
+            }
+
+            pen_x += glyph.advance;
+        }
+    }
+
+    Bitmap {
+        width,
+        height,
+        data: Bitmap::encode(bits.into_iter()),
+        mask: None,
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{
+    args::{Args, Spanned},
+    scale::Scaler,
+};
+use image::{imageops, imageops::FilterType, RgbaImage};
+use syn::parse::{Error, Result};
+
+/// Geometric operations applied to an image before it's converted.
+pub(crate) struct Transform {
+    region: Option<Spanned<[u32; 4]>>,
+    size: Option<Spanned<[u32; 2]>>,
+    rotate: Rotation,
+    flip_x: bool,
+    flip_y: bool,
+    scale: Option<Spanned<u32>>,
+    scaler: Scaler,
+}
+
+/// Upper bound on the number of pixels of an upscaled image, far beyond any display,
+/// so that a typo in `scale` fails instead of exhausting the memory.
+const MAX_SCALED_PIXELS: u64 = 1 << 24;
+
+/// Clockwise rotation.
+#[derive(Clone, Copy)]
+enum Rotation {
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl Transform {
+    pub(crate) fn from_args(args: &mut Args) -> Result<Self> {
+        let region = args.tuple("region")?;
+        let size = args.tuple("size")?;
+        let rotate = match args.int::<u32>("rotate")? {
+            None | Some(Spanned { value: 0, .. }) => Rotation::None,
+            Some(Spanned { value: 90, .. }) => Rotation::Quarter,
+            Some(Spanned { value: 180, .. }) => Rotation::Half,
+            Some(Spanned { value: 270, .. }) => Rotation::ThreeQuarters,
+            Some(Spanned { value, span }) => {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "invalid `rotate` value {}, expected 0, 90, 180 or 270",
+                        value
+                    ),
+                ))
+            }
+        };
+        let flip_x = args.flag("flip_x")?;
+        let flip_y = args.flag("flip_y")?;
+        let scale = args.int::<u32>("scale")?;
+        let scaler = args.string("scaler")?;
+
+        let factor = scale.as_ref().map_or(1, |scale| scale.value);
+        if let Some(Spanned { value: 0, span }) = scale {
+            return Err(Error::new(span, "`scale` must be at least 1"));
+        }
+
+        let scaler = match scaler {
+            Some(lit) => {
+                let scaler = Scaler::from_lit(&lit)?;
+                if !scaler.supports(factor) {
+                    return Err(Error::new(
+                        lit.span(),
+                        format!(
+                            "scaler \"{}\" can't upscale by a factor of {}",
+                            lit.value(),
+                            factor
+                        ),
+                    ));
+                }
+                scaler
+            }
+            None => Scaler::default(),
+        };
+
+        Ok(Self {
+            region,
+            size,
+            rotate,
+            flip_x,
+            flip_y,
+            scale,
+            scaler,
+        })
+    }
+
+    /// Checks if pixels of the transformed image may blend colors of the original
+    /// one.
+    pub(crate) fn resamples(&self) -> bool {
+        self.size.is_some()
+    }
+
+    /// Size that a vector image should be rendered at, so that it doesn't have to be
+    /// resized. There's none if the image is first cropped to a `region`, which is
+    /// given in the coordinates of the image at its own size.
+    pub(crate) fn render_size(&self) -> Option<[u32; 2]> {
+        if self.region.is_some() {
+            return None;
+        }
+
+        self.size
+            .as_ref()
+            .map(|size| size.value)
+            .filter(|&[width, height]| width > 0 && height > 0)
+    }
+
+    pub(crate) fn apply(&self, img: RgbaImage) -> Result<RgbaImage> {
+        let mut img = img;
+
+        if let Some(Spanned {
+            value: [x, y, width, height],
+            span,
+        }) = self.region
+        {
+            let fits = x
+                .checked_add(width)
+                .zip(y.checked_add(height))
+                .is_some_and(|(right, bottom)| right <= img.width() && bottom <= img.height());
+
+            if !fits {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "region of size {}x{} at {},{} extends past the {}x{} image",
+                        width,
+                        height,
+                        x,
+                        y,
+                        img.width(),
+                        img.height()
+                    ),
+                ));
+            }
+
+            img = imageops::crop_imm(&img, x, y, width, height).to_image();
+        }
+
+        if let Some(Spanned {
+            value: [width, height],
+            span,
+        }) = self.size
+        {
+            if width == 0 || height == 0 {
+                return Err(Error::new(span, "`size` must not be zero"));
+            }
+
+            img = imageops::resize(&img, width, height, FilterType::Triangle);
+        }
+
+        img = match self.rotate {
+            Rotation::None => img,
+            Rotation::Quarter => imageops::rotate90(&img),
+            Rotation::Half => imageops::rotate180(&img),
+            Rotation::ThreeQuarters => imageops::rotate270(&img),
+        };
+
+        if self.flip_x {
+            imageops::flip_horizontal_in_place(&mut img);
+        }
+        if self.flip_y {
+            imageops::flip_vertical_in_place(&mut img);
+        }
+
+        if let Some(Spanned {
+            value: factor,
+            span,
+        }) = self.scale
+        {
+            let fits = img
+                .width()
+                .checked_mul(factor)
+                .zip(img.height().checked_mul(factor))
+                .is_some_and(|(width, height)| {
+                    u64::from(width) * u64::from(height) <= MAX_SCALED_PIXELS
+                });
+
+            if !fits {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "scaling the {}x{} image by {} exceeds the limit of {} pixels",
+                        img.width(),
+                        img.height(),
+                        factor,
+                        MAX_SCALED_PIXELS
+                    ),
+                ));
+            }
+
+            img = self.scaler.apply(img, factor);
+        }
+
+        Ok(img)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    fn scale(factor: u32) -> Transform {
+        Transform {
+            region: None,
+            size: None,
+            rotate: Rotation::None,
+            flip_x: false,
+            flip_y: false,
+            scale: Some(Spanned {
+                value: factor,
+                span: Span::call_site(),
+            }),
+            scaler: Scaler::Nearest,
+        }
+    }
+
+    #[test]
+    fn test_scale() {
+        let img = scale(3).apply(RgbaImage::new(4, 2)).unwrap();
+
+        assert_eq!(img.dimensions(), (12, 6));
+    }
+
+    #[test]
+    fn test_scale_exceeding_limit() {
+        let error = scale(4096).apply(RgbaImage::new(2, 1)).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "scaling the 2x1 image by 4096 exceeds the limit of 16777216 pixels"
+        );
+    }
+
+    #[test]
+    fn test_scale_overflow() {
+        let error = scale(u32::MAX).apply(RgbaImage::new(2, 2)).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "scaling the 2x2 image by 4294967295 exceeds the limit of 16777216 pixels"
+        );
+    }
+}
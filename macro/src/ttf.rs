@@ -0,0 +1,182 @@
+use crate::{
+    args::Args,
+    bitmap::Bitmap,
+    font::{Font, FontArgs, Glyph, Metrics},
+    path::{self, RelativeTo},
+    track_file_if_available,
+};
+use ab_glyph::{Font as _, FontVec, PxScale, ScaleFont};
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use std::fs;
+use syn::{
+    parse::{Error, Parse, ParseStream, Result},
+    LitStr,
+};
+
+/// Input and output of the `ttf_font!` macro.
+pub(crate) struct TtfFont {
+    font: Font,
+}
+
+/// Arguments of the `ttf_font!` macro, except for the path of the font.
+pub(crate) struct TtfArgs {
+    size: u32,
+    threshold: u8,
+    font_args: FontArgs,
+    relative_to: Option<RelativeTo>,
+}
+
+impl Parse for TtfFont {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_str = input.parse::<LitStr>()?;
+        let mut args = input.parse::<Args>()?;
+        let ttf_args = TtfArgs::from_args(&mut args, input.span())?;
+        args.finish()?;
+
+        let font = ttf_args.load(&lit_str)?;
+        font.record(lit_str.span())?;
+
+        Ok(Self { font })
+    }
+}
+
+impl TtfArgs {
+    /// Takes the arguments, reporting a missing `size` at `span`.
+    pub(crate) fn from_args(args: &mut Args, span: Span) -> Result<Self> {
+        let size = args.int::<u32>("size")?.ok_or_else(|| {
+            Error::new(
+                span,
+                "missing the size of the font in pixels, e.g. `size = 12`",
+            )
+        })?;
+        if size.value == 0 {
+            return Err(Error::new(size.span, "`size` must not be zero"));
+        }
+        let threshold = args
+            .int::<u8>("threshold")?
+            .map_or(128, |threshold| threshold.value);
+        let font_args = FontArgs::from_args(args)?;
+        let relative_to = args
+            .string("relative_to")?
+            .map(|lit| RelativeTo::from_lit(&lit))
+            .transpose()?;
+
+        Ok(Self {
+            size: size.value,
+            threshold,
+            font_args,
+            relative_to,
+        })
+    }
+
+    /// Reads the font at the path written in `lit_str` and rasterizes its glyphs.
+    pub(crate) fn load(self, lit_str: &LitStr) -> Result<Font> {
+        let Self {
+            size,
+            threshold,
+            font_args,
+            relative_to,
+        } = self;
+
+        let path = path::resolve(lit_str, relative_to)?;
+        track_file_if_available(&path);
+
+        let data = fs::read(&path).map_err(|error| {
+            Error::new(
+                lit_str.span(),
+                format!("couldn't read {}: {}", path.display(), error),
+            )
+        })?;
+        let ttf = FontVec::try_from_vec(data).map_err(|error| {
+            Error::new(
+                lit_str.span(),
+                format!("couldn't parse {}: {}", path.display(), error),
+            )
+        })?;
+
+        // `size` is the size of the em square, while a `PxScale` is the distance
+        // between the ascent and the descent
+        let units_per_em = ttf.units_per_em().unwrap_or(1000.0);
+        let scale = PxScale::from(size as f32 * ttf.height_unscaled() / units_per_em);
+        let scaled = ttf.as_scaled(scale);
+
+        let available = ttf
+            .codepoint_ids()
+            .filter(|(id, _)| id.0 != 0)
+            .map(|(_, c)| c)
+            .collect::<Vec<_>>();
+        let printable = (' '..='~').filter(|c| available.contains(c)).collect();
+        let subset = font_args.subset(&available, printable)?;
+
+        let ascent = scaled.ascent().round() as isize;
+        let glyphs = subset
+            .iter()
+            .map(|&c| {
+                let id = ttf.glyph_id(c);
+                let advance = scaled.h_advance(id).round().max(0.0) as usize;
+                let outline = ttf.outline_glyph(id.with_scale(scale));
+
+                let (bitmap, offset) = match outline {
+                    Some(outline) => {
+                        let bounds = outline.px_bounds();
+                        let width = bounds.width() as usize;
+                        let height = bounds.height() as usize;
+                        let mut bits = vec![false; width * height];
+                        outline.draw(|x, y, coverage| {
+                            let (x, y) = (x as usize, y as usize);
+                            if x < width && y < height {
+                                bits[y * width + x] = coverage * 255.0 >= threshold as f32;
+                            }
+                        });
+
+                        let bitmap = Bitmap {
+                            width,
+                            height,
+                            data: Bitmap::encode(bits.into_iter()),
+                            mask: None,
+                        };
+                        let offset = [bounds.min.x as isize, ascent + bounds.min.y as isize];
+                        (bitmap, offset)
+                    }
+                    // Glyphs without an outline, like the space, only advance the pen
+                    None => {
+                        let bitmap = Bitmap {
+                            width: 0,
+                            height: 0,
+                            data: Vec::new(),
+                            mask: None,
+                        };
+                        (bitmap, [0, 0])
+                    }
+                };
+
+                Glyph {
+                    char: c,
+                    bitmap,
+                    offset,
+                    advance: advance + font_args.letter_spacing,
+                }
+            })
+            .collect();
+
+        let descent = (-scaled.descent()).round() as isize;
+        let line_gap = scaled.line_gap().round() as isize;
+        let metrics = Metrics {
+            ascent: ascent.max(0) as usize,
+            descent: descent.max(0) as usize,
+            line_height: (ascent + descent + line_gap).max(0) as usize + font_args.line_spacing,
+        };
+        let span = font_args
+            .chars
+            .as_ref()
+            .map_or(lit_str.span(), |chars| chars.span);
+        Font::new(glyphs, metrics, font_args.fallback, path, span)
+    }
+}
+
+impl ToTokens for TtfFont {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.font.to_tokens(tokens);
+    }
+}
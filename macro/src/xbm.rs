@@ -0,0 +1,101 @@
+use image::{Rgba, RgbaImage};
+
+/// Parses the source of an XBM file, in either the X11 format, with rows of bytes,
+/// or the older X10 format, with rows of 16-bit words.
+///
+/// Set bits are the foreground, and become black, with the least significant bit of
+/// every byte or word being the leftmost pixel.
+pub(crate) fn parse(source: &str) -> Result<RgbaImage, String> {
+    let mut width = None;
+    let mut height = None;
+
+    for line in source.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("#define") {
+            continue;
+        }
+        let (Some(name), Some(value)) = (words.next(), words.next()) else {
+            continue;
+        };
+
+        if name == "width" || name.ends_with("_width") {
+            width = Some(integer(value).ok_or("invalid width")?);
+        } else if name == "height" || name.ends_with("_height") {
+            height = Some(integer(value).ok_or("invalid height")?);
+        }
+    }
+
+    let width = width.ok_or("missing width")? as usize;
+    let height = height.ok_or("missing height")? as usize;
+
+    let start = source.find('{').ok_or("missing pixel data")?;
+    let end = source[start..]
+        .find('}')
+        .map(|end| start + end)
+        .ok_or("unterminated pixel data")?;
+    let word_bits = word_bits(&source[..start])?;
+
+    let words = source[start + 1..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            integer(word)
+                .filter(|&value| value < 1 << word_bits)
+                .ok_or_else(|| format!("invalid value {}", word))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let row_len = width.div_ceil(word_bits);
+    if words.len() < row_len * height {
+        return Err(format!(
+            "expected {} values for an image of size {}x{}, found {}",
+            row_len * height,
+            width,
+            height,
+            words.len(),
+        ));
+    }
+
+    Ok(RgbaImage::from_fn(width as u32, height as u32, |x, y| {
+        let (x, y) = (x as usize, y as usize);
+        let word = words[y * row_len + x / word_bits];
+
+        if word & (1 << (x % word_bits)) != 0 {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    }))
+}
+
+/// Size of the elements of the array, whose declaration ends the given source, e.g.
+/// `static unsigned char name_bits[] =`.
+fn word_bits(source: &str) -> Result<usize, String> {
+    let declaration = source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let declaration = declaration.rsplit(';').next().unwrap_or_default();
+    let mut tokens = declaration
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|token| !token.is_empty());
+
+    match tokens.find(|&token| token == "char" || token == "short") {
+        Some("char") => Ok(8),
+        Some(_) => Ok(16),
+        None => Err("expected an array of `char` or `short` values".into()),
+    }
+}
+
+/// Parses a C integer literal, either decimal or hexadecimal.
+fn integer(literal: &str) -> Option<u32> {
+    match literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        Some(digits) => u32::from_str_radix(digits, 16).ok(),
+        None => literal.parse().ok(),
+    }
+}
@@ -0,0 +1,251 @@
+use crate::Stamp;
+
+/// Sequence of stamps, each shown for a given amount of time.
+///
+/// Animations are produced by the [`animation!`](crate::animation!) macro. The
+/// frames are usually [`Stamp`]s, or [`MaskedStamp`](crate::MaskedStamp)s if the
+/// macro is given the `transparent` argument.
+///
+/// All times are expressed in milliseconds. Animations loop, i.e. once the last
+/// frame has been shown, the first one is shown again.
+#[derive(Debug, Clone)]
+pub struct Animation<S: 'static = Stamp> {
+    frames: &'static [S],
+    delays: &'static [u32],
+}
+
+impl<S> Animation<S> {
+    /// Constructs a new animation from its frames and the number of milliseconds
+    /// each of them is shown for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no frames, or if the number of frames and delays differ.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{Animation, Stamp};
+    ///
+    /// static FRAMES: [Stamp; 2] = unsafe {
+    ///     [
+    ///         Stamp::from_raw(2, 1, [0b10_000000].as_ptr()),
+    ///         Stamp::from_raw(2, 1, [0b01_000000].as_ptr()),
+    ///     ]
+    /// };
+    /// static DELAYS: [u32; 2] = [100, 200];
+    /// static BLINK: Animation = Animation::new(&FRAMES, &DELAYS);
+    ///
+    /// assert_eq!(BLINK.frame_count(), 2);
+    /// ```
+    pub const fn new(frames: &'static [S], delays: &'static [u32]) -> Self {
+        assert!(
+            !frames.is_empty(),
+            "an animation must have at least one frame"
+        );
+        assert!(
+            frames.len() == delays.len(),
+            "an animation must have exactly one delay per frame"
+        );
+
+        Self { frames, delays }
+    }
+
+    /// Number of frames in the animation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.frame_count(), 2);
+    /// ```
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// All frames of the animation, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Color, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// let first_colors = BLINK
+    ///     .frames()
+    ///     .iter()
+    ///     .map(|frame| frame.get_color(0, 0))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(first_colors, [Color::White, Color::Black]);
+    /// ```
+    #[inline]
+    pub fn frames(&self) -> &'static [S] {
+        self.frames
+    }
+
+    /// Number of milliseconds each frame is shown for, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.delays(), [100, 200]);
+    /// ```
+    #[inline]
+    pub fn delays(&self) -> &'static [u32] {
+        self.delays
+    }
+
+    /// Returns the frame at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Color, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.frame(1).get_color(0, 0), Color::Black);
+    /// assert_eq!(BLINK.frame(1).get_color(1, 0), Color::White);
+    /// ```
+    #[inline]
+    pub fn frame(&self, index: usize) -> &'static S {
+        &self.frames[index]
+    }
+
+    /// Returns the number of milliseconds the frame at `index` is shown for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.delay(0), 100);
+    /// assert_eq!(BLINK.delay(1), 200);
+    /// ```
+    #[inline]
+    pub fn delay(&self, index: usize) -> u32 {
+        self.delays[index]
+    }
+
+    /// Number of milliseconds it takes to show every frame once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.duration(), 300);
+    /// ```
+    pub fn duration(&self) -> u32 {
+        self.delays
+            .iter()
+            .fold(0, |duration, delay| duration.saturating_add(*delay))
+    }
+
+    /// Returns the index of the frame that is shown `elapsed` milliseconds after
+    /// the animation has started.
+    ///
+    /// If the duration of the animation is zero, the first frame is always shown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.frame_index_at(0), 0);
+    /// assert_eq!(BLINK.frame_index_at(99), 0);
+    /// assert_eq!(BLINK.frame_index_at(100), 1);
+    /// assert_eq!(BLINK.frame_index_at(299), 1);
+    /// assert_eq!(BLINK.frame_index_at(300), 0); // loops around
+    /// ```
+    pub fn frame_index_at(&self, elapsed: u32) -> usize {
+        let duration = self.duration();
+        if duration == 0 {
+            return 0;
+        }
+
+        let mut remaining = elapsed % duration;
+        for (index, delay) in self.delays.iter().enumerate() {
+            if remaining < *delay {
+                return index;
+            }
+            remaining -= delay;
+        }
+
+        self.delays.len() - 1
+    }
+
+    /// Returns the frame that is shown `elapsed` milliseconds after the animation has
+    /// started, see [`frame_index_at`](Animation::frame_index_at).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{animation, Animation, Color, Stamp};
+    ///
+    /// # static FRAMES: [Stamp; 2] = unsafe { [Stamp::from_raw(2, 1, [0b10_000000].as_ptr()), Stamp::from_raw(2, 1, [0b01_000000].as_ptr())] };
+    /// # macro_rules! animation {
+    /// #     ($path:literal) => { Animation::new(&FRAMES, &[100, 200]) };
+    /// # }
+    /// static BLINK: Animation = animation!("blink.gif");
+    ///
+    /// assert_eq!(BLINK.frame_at(50).get_color(0, 0), Color::White);
+    /// assert_eq!(BLINK.frame_at(150).get_color(0, 0), Color::Black);
+    /// ```
+    #[inline]
+    pub fn frame_at(&self, elapsed: u32) -> &'static S {
+        &self.frames[self.frame_index_at(elapsed)]
+    }
+}
@@ -37,39 +37,61 @@ impl Data {
         }
     }
 
-    /// Returns a byte at `idx`, without doing bounds checking.
-    ///
-    /// # Safety
-    ///
-    /// Calling this method with an out-of-bounds index is undefined behavior, even if
-    /// the resulting reference is not used.
-    pub unsafe fn get_unchecked(&self, idx: usize) -> u8 {
-        let ptr = self.as_ptr().add(idx);
-        Self::deref(ptr)
+    const_unless_progmem! {
+        /// Returns a byte at `idx`, without doing bounds checking.
+        ///
+        /// # Safety
+        ///
+        /// Calling this method with an out-of-bounds index is undefined behavior, even if
+        /// the resulting reference is not used.
+        pub unsafe fn get_unchecked(&self, idx: usize) -> u8 {
+            let ptr = self.as_ptr().add(idx);
+            Self::deref(ptr)
+        }
     }
 
-    /// Return the raw pointer to the inner value.
-    ///
-    /// If the `"progmem"` feature is enabled, the returned pointer must not be
-    /// dereferenced via the default Rust operations.
-    pub fn as_ptr(&self) -> *const u8 {
-        cfg_if! {
-            if #[cfg(feature = "progmem")] {
-                self.source.as_ptr()
-            } else {
-                self.source
+    const_unless_progmem! {
+        /// Returns the bit at `idx`, counting from the most significant bit of the first
+        /// byte, without doing bounds checking.
+        ///
+        /// # Safety
+        ///
+        /// The byte containing the bit must be in bounds, see
+        /// [`get_unchecked`](Data::get_unchecked).
+        pub unsafe fn get_bit_unchecked(&self, idx: usize) -> bool {
+            let byte = self.get_unchecked(idx / 8);
+            let mask = 0b10000000 >> (idx % 8);
+
+            byte & mask != 0
+        }
+    }
+
+    const_unless_progmem! {
+        /// Return the raw pointer to the inner value.
+        ///
+        /// If the `"progmem"` feature is enabled, the returned pointer must not be
+        /// dereferenced via the default Rust operations.
+        pub fn as_ptr(&self) -> *const u8 {
+            cfg_if! {
+                if #[cfg(feature = "progmem")] {
+                    self.source.as_ptr()
+                } else {
+                    self.source
+                }
             }
         }
     }
 
-    unsafe fn deref(ptr: *const u8) -> u8 {
-        cfg_if! {
-            if #[cfg(feature = "progmem")] {
-                // Since we're building with the `"progmem"` feature, `ptr` is valid in the program
-                // domain.
-                read_byte(ptr)
-            } else {
-                *ptr
+    const_unless_progmem! {
+        unsafe fn deref(ptr: *const u8) -> u8 {
+            cfg_if! {
+                if #[cfg(feature = "progmem")] {
+                    // Since we're building with the `"progmem"` feature, `ptr` is valid in the
+                    // program domain.
+                    read_byte(ptr)
+                } else {
+                    *ptr
+                }
             }
         }
     }
@@ -0,0 +1,307 @@
+use crate::{Stamp, TextPixels};
+use core::cmp::Ordering;
+
+/// Bitmap font &mdash; a set of [`Glyph`]s, each drawn for a range of characters.
+///
+/// Fonts are produced by the [`font!`](crate::font!) macro. Each glyph is a
+/// [`Stamp`], in which [`White`](crate::Color::White) pixels are the ink, and
+/// [`Black`](crate::Color::Black) ones are the background.
+///
+/// Text is laid out from the top-left corner, glyph after glyph, each placed at the
+/// current _pen position_, which then moves right by the glyph's
+/// [advance](Glyph::advance). A `'\n'` moves the pen back to the left edge and down
+/// by the font's [line height](Font::line_height).
+#[derive(Debug, Clone)]
+pub struct Font {
+    glyphs: &'static [Glyph],
+    ranges: &'static [CharRange],
+    ascent: usize,
+    descent: usize,
+    line_height: usize,
+    fallback: Option<char>,
+}
+
+/// Single character of a [`Font`].
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    stamp: Stamp,
+    offset: [isize; 2],
+    advance: usize,
+}
+
+/// Range of consecutive characters, whose glyphs are consecutive in the glyph array
+/// of a [`Font`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharRange {
+    first: char,
+    last: char,
+    glyph: usize,
+}
+
+impl Font {
+    /// Constructs a new font. Using the [`font!`](crate::font!) macro is
+    /// recommended instead, which builds the glyphs and the ranges for you.
+    ///
+    /// `ranges` must be sorted and must not overlap. `ascent` and `descent` are the
+    /// distances from the baseline to the top and the bottom of a line, respectively.
+    /// `fallback` is the character whose glyph is used for characters that the font
+    /// doesn't have.
+    pub const fn new(
+        glyphs: &'static [Glyph],
+        ranges: &'static [CharRange],
+        ascent: usize,
+        descent: usize,
+        line_height: usize,
+        fallback: Option<char>,
+    ) -> Self {
+        Self {
+            glyphs,
+            ranges,
+            ascent,
+            descent,
+            line_height,
+            fallback,
+        }
+    }
+
+    /// Distance from the top of a line of text to its baseline, in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// assert_eq!(FONT.ascent(), 2);
+    /// ```
+    #[inline]
+    pub fn ascent(&self) -> usize {
+        self.ascent
+    }
+
+    /// Distance from the baseline of a line of text to its bottom, in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// assert_eq!(FONT.descent(), 0);
+    /// ```
+    #[inline]
+    pub fn descent(&self) -> usize {
+        self.descent
+    }
+
+    /// Distance between the tops of two consecutive lines of text, in pixels. It's at
+    /// least the sum of the [ascent](Font::ascent) and the [descent](Font::descent).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// assert_eq!(FONT.line_height(), 3);
+    /// ```
+    #[inline]
+    pub fn line_height(&self) -> usize {
+        self.line_height
+    }
+
+    /// Returns the glyph of `c`, or of the fallback character if the font doesn't
+    /// have one for `c`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// assert_eq!(FONT.glyph('b').map(|glyph| glyph.advance()), Some(2));
+    /// assert!(FONT.glyph('c').is_none());
+    /// ```
+    pub fn glyph(&self, c: char) -> Option<&'static Glyph> {
+        self.find(c)
+            .or_else(|| self.fallback.and_then(|fallback| self.find(fallback)))
+    }
+
+    /// Returns the stamp of the glyph of `c`, see [`glyph`](Font::glyph).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// assert_eq!(FONT.stamp('a').map(|stamp| stamp.size()), Some([2, 2]));
+    /// ```
+    #[inline]
+    pub fn stamp(&self, c: char) -> Option<&'static Stamp> {
+        self.glyph(c).map(Glyph::stamp)
+    }
+
+    /// Width and height of the area covered by the pen when laying out `text`, in
+    /// pixels. The width is the sum of the advances of the widest line.
+    ///
+    /// Characters without a glyph take no space.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// assert_eq!(FONT.text_size("abba"), [10, 3]);
+    /// assert_eq!(FONT.text_size("ab\nbba"), [7, 6]);
+    /// ```
+    pub fn text_size(&self, text: &str) -> [usize; 2] {
+        let mut width = 0;
+        let mut lines = 0;
+
+        for line in text.split('\n') {
+            let line_width = line
+                .chars()
+                .filter_map(|c| self.glyph(c))
+                .map(Glyph::advance)
+                .sum();
+            width = usize::max(width, line_width);
+            lines += 1;
+        }
+
+        [width, lines * self.line_height]
+    }
+
+    /// Returns an iterator over the pixels of every glyph of `text`, laid out with the
+    /// pen starting at _(0, 0)_.
+    ///
+    /// Pixels of glyphs that extend above or to the left of _(0, 0)_ are skipped.
+    /// Glyphs that overlap yield the pixels they have in common more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{font, Color, Font, Stamp};
+    ///
+    /// # use stockbook::{CharRange, Glyph};
+    /// # static GLYPHS: [Glyph; 2] = unsafe { [Glyph::new(Stamp::from_raw(2, 2, [0b1111_0000].as_ptr()), [0, 0], 3), Glyph::new(Stamp::from_raw(1, 2, [0b11_000000].as_ptr()), [0, 0], 2)] };
+    /// # static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    /// # macro_rules! font {
+    /// #     ($path:literal, glyph = $size:expr, chars = $chars:literal) => { Font::new(&GLYPHS, &RANGES, 2, 0, 3, None) };
+    /// # }
+    /// static FONT: Font = font!("font.png", glyph = (2, 2), chars = "ab");
+    ///
+    /// let ink = FONT
+    ///     .text("ba")
+    ///     .filter(|&(_, _, color)| color == Color::White)
+    ///     .map(|(x, y, _)| (x, y))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(ink, [(0, 0), (0, 1), (2, 0), (3, 0), (2, 1), (3, 1)]);
+    /// ```
+    #[inline]
+    pub fn text<'a>(&'a self, text: &'a str) -> TextPixels<'a> {
+        TextPixels::new(self, text)
+    }
+
+    fn find(&self, c: char) -> Option<&'static Glyph> {
+        let index = self
+            .ranges
+            .binary_search_by(|range| {
+                if range.last < c {
+                    Ordering::Less
+                } else if range.first > c {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .ok()?;
+        let range = &self.ranges[index];
+
+        self.glyphs
+            .get(range.glyph + (c as usize - range.first as usize))
+    }
+}
+
+impl Glyph {
+    /// Constructs a new glyph drawn with `stamp`, whose top-left corner is `offset`
+    /// away from the pen position, and which moves the pen by `advance` pixels.
+    pub const fn new(stamp: Stamp, offset: [isize; 2], advance: usize) -> Self {
+        Self {
+            stamp,
+            offset,
+            advance,
+        }
+    }
+
+    /// Pixels of the glyph.
+    #[inline]
+    pub fn stamp(&self) -> &Stamp {
+        &self.stamp
+    }
+
+    /// Position of the top-left corner of the glyph's stamp relative to the pen
+    /// position, in pixels.
+    #[inline]
+    pub fn offset(&self) -> [isize; 2] {
+        self.offset
+    }
+
+    /// Number of pixels the pen moves right by after drawing the glyph.
+    #[inline]
+    pub fn advance(&self) -> usize {
+        self.advance
+    }
+}
+
+impl CharRange {
+    /// Constructs a new range of the characters from `first` to `last`, inclusive,
+    /// whose glyphs start at index `glyph`.
+    pub const fn new(first: char, last: char, glyph: usize) -> Self {
+        Self { first, last, glyph }
+    }
+}
@@ -1,3 +1,7 @@
+mod masked_pixels;
 mod pixels;
+mod text_pixels;
 
+pub use self::masked_pixels::*;
 pub use self::pixels::*;
+pub use self::text_pixels::*;
@@ -0,0 +1,81 @@
+use crate::{Color, MaskedColor, MaskedStamp, Pixels};
+use core::iter::FusedIterator;
+
+/// An iterator that yields all pixels of a [`MaskedStamp`].
+///
+/// This type is created by the [`pixels`](MaskedStamp::pixels) method on
+/// [`MaskedStamp`]. See its documentation for more details.
+#[derive(Debug, Clone)]
+pub struct MaskedPixels<'a> {
+    pixels: Pixels<'a>,
+    stamp: &'a MaskedStamp,
+}
+
+impl<'a> MaskedPixels<'a> {
+    pub(crate) fn new(stamp: &'a MaskedStamp) -> Self {
+        Self {
+            pixels: stamp.stamp().pixels(),
+            stamp,
+        }
+    }
+
+    fn mask(&self, (x, y, color): (usize, usize, Color)) -> (usize, usize, MaskedColor) {
+        // SAFETY: the coordinates come from an iterator over the same stamp
+        if unsafe { self.stamp.is_opaque_unchecked(x, y) } {
+            (x, y, color.into())
+        } else {
+            (x, y, MaskedColor::Transparent)
+        }
+    }
+}
+
+impl Iterator for MaskedPixels<'_> {
+    type Item = (usize, usize, MaskedColor);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pixel = self.pixels.next()?;
+        Some(self.mask(pixel))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pixels.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for MaskedPixels<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pixel = self.pixels.next_back()?;
+        Some(self.mask(pixel))
+    }
+}
+
+impl ExactSizeIterator for MaskedPixels<'_> {}
+
+impl FusedIterator for MaskedPixels<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_size_stamp() {
+        let stamp = unsafe { MaskedStamp::from_raw(0, 0, [].as_ptr(), [].as_ptr()) };
+        let mut pixels = stamp.pixels();
+
+        assert_eq!(pixels.next(), None);
+    }
+
+    #[test]
+    fn test_double_ended() {
+        let stamp =
+            unsafe { MaskedStamp::from_raw(2, 2, [0b1010_0000].as_ptr(), [0b1101_0000].as_ptr()) };
+        let mut pixels = stamp.pixels();
+
+        assert_eq!(pixels.next(), Some((0, 0, MaskedColor::White)));
+        assert_eq!(pixels.next_back(), Some((1, 1, MaskedColor::Black)));
+        assert_eq!(pixels.next_back(), Some((0, 1, MaskedColor::Transparent)));
+        assert_eq!(pixels.next(), Some((1, 0, MaskedColor::Black)));
+        assert_eq!(pixels.next(), None);
+        assert_eq!(pixels.next_back(), None);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Color, Font, Pixels};
+use core::{iter::FusedIterator, str::Chars};
+
+/// An iterator that yields the pixels of every glyph of a piece of text.
+///
+/// This type is created by the [`text`](Font::text) method on [`Font`]. See its
+/// documentation for more details.
+#[derive(Debug, Clone)]
+pub struct TextPixels<'a> {
+    font: &'a Font,
+    chars: Chars<'a>,
+    pen: [usize; 2],
+    glyph: Option<(Pixels<'static>, [isize; 2])>,
+}
+
+impl<'a> TextPixels<'a> {
+    pub(crate) fn new(font: &'a Font, text: &'a str) -> Self {
+        Self {
+            font,
+            chars: text.chars(),
+            pen: [0, 0],
+            glyph: None,
+        }
+    }
+
+    /// Moves on to the glyph of the next character, returning `false` at the end of
+    /// the text.
+    fn next_glyph(&mut self) -> bool {
+        let Some(c) = self.chars.next() else {
+            self.glyph = None;
+            return false;
+        };
+
+        if c == '\n' {
+            self.pen = [0, self.pen[1] + self.font.line_height()];
+            self.glyph = None;
+        } else if let Some(glyph) = self.font.glyph(c) {
+            let [x, y] = self.pen;
+            let [offset_x, offset_y] = glyph.offset();
+            let origin = [x as isize + offset_x, y as isize + offset_y];
+
+            self.glyph = Some((glyph.stamp().pixels(), origin));
+            self.pen[0] += glyph.advance();
+        }
+
+        true
+    }
+}
+
+impl Iterator for TextPixels<'_> {
+    type Item = (usize, usize, Color);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((pixels, [origin_x, origin_y])) = &mut self.glyph {
+                for (x, y, color) in pixels.by_ref() {
+                    let x = *origin_x + x as isize;
+                    let y = *origin_y + y as isize;
+                    if x >= 0 && y >= 0 {
+                        return Some((x as usize, y as usize, color));
+                    }
+                }
+            }
+
+            if !self.next_glyph() {
+                return None;
+            }
+        }
+    }
+}
+
+impl FusedIterator for TextPixels<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CharRange, Font, Glyph, Stamp};
+
+    static GLYPHS: [Glyph; 2] = unsafe {
+        [
+            Glyph::new(Stamp::from_raw(1, 1, [0b1000_0000].as_ptr()), [0, 0], 2),
+            Glyph::new(Stamp::from_raw(2, 1, [0b1100_0000].as_ptr()), [-1, 1], 1),
+        ]
+    };
+    static RANGES: [CharRange; 1] = [CharRange::new('a', 'b', 0)];
+    static FONT: Font = Font::new(&GLYPHS, &RANGES, 2, 1, 3, None);
+
+    #[test]
+    fn test_empty_text() {
+        assert_eq!(FONT.text("").next(), None);
+    }
+
+    #[test]
+    fn test_missing_glyphs() {
+        let pixels = FONT.text("?a?").map(|(x, y, _)| (x, y));
+
+        assert!(pixels.eq([(0, 0)]));
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        let pixels = FONT.text("a\naa").map(|(x, y, _)| (x, y));
+
+        assert!(pixels.eq([(0, 0), (0, 3), (2, 3)]));
+    }
+
+    #[test]
+    fn test_negative_offsets() {
+        let pixels = FONT.text("bab").map(|(x, y, _)| (x, y));
+
+        assert!(pixels.eq([(0, 1), (1, 0), (2, 1), (3, 1)]));
+    }
+}
@@ -65,12 +65,137 @@
 //! # assert_eq!(unsafe { ACTUAL_PIXELS.as_slice() }, EXPECTED_PIXELS);
 //! ```
 //!
+//! ## Sprite sheets
+//!
+//! The [`stamp_sheet!`] macro slices a single image into an array of [`Stamp`]s,
+//! e.g. `stamp_sheet!("tiles.png", tile = (8, 8))`, with optional `margin` and
+//! `spacing` between the tiles. All of the tiles share one array of pixel data, in
+//! which identical tiles are stored only once.
+//!
+//! ## Fonts
+//!
+//! The [`font!`] macro slices a glyph sheet into a [`Font`], e.g.
+//! `font!("font.png", glyph = (5, 7), chars = ' '..='~')`, optionally with
+//! proportional glyph widths. A font looks up the [`Glyph`] of a character, and
+//! lays out a whole string with [`Font::text`], which yields its pixels the same
+//! way [`Stamp::pixels`] does.
+//!
+//! Fonts in the BDF format, like Terminus, Spleen, or the X11 `misc-fixed` fonts,
+//! can be included with the [`bdf_font!`] macro instead, optionally limited to the
+//! characters that are actually used, e.g. `bdf_font!("font.bdf", chars = "0123456789")`.
+//!
+//! TrueType and OpenType fonts can be rasterized at a given size with the
+//! `ttf_font!` macro, e.g. `ttf_font!("font.ttf", size = 12)`, which is available
+//! with the `ttf` feature.
+//!
+//! Static labels can be rendered at compile time with the [`text_stamp!`] macro,
+//! e.g. `text_stamp!("Press OK", font = "font.bdf", align = "center", max_width = 64)`,
+//! which yields a single `Stamp` without including the font in the program.
+//!
+//! ## ASCII art
+//!
+//! Tiny images can be drawn right in the code with the [`stamp_ascii!`] macro, which
+//! takes rows of `#` (white) and `.` (black) characters, e.g.
+//! `stamp_ascii!("#.#\n.#.")`, or a `.txt` file with the same contents.
+//!
+//! ## Directories
+//!
+//! The [`stamps!`] macro includes every image matching a glob pattern, e.g.
+//! `stamps!(pub mod icons, "assets/icons/*.png")`, as a module with one `static`
+//! per file, named after the file, like `icons::WIFI`.
+//!
+//! ## Enums
+//!
+//! The [`stamp_enum!`] macro defines an enum whose variants are each assigned an
+//! image, e.g. `Home = "icons/home.png"`, so that stamps can be passed around and
+//! matched on as plain values. The generated enum has an `ALL` array of its
+//! variants, and `stamp` and `name` methods.
+//!
+//! ## Animations
+//!
+//! The [`animation!`] macro decodes every frame of an animated GIF or APNG file,
+//! e.g. `animation!("spinner.gif")`, and yields an [`Animation`], which keeps the
+//! frames along with their delays, and tells which frame to show after a given
+//! number of milliseconds.
+//!
+//! ## Transparency
+//!
+//! Passing `transparent = "alpha"`, or a color key like `transparent = "#ff00ff"`,
+//! to the [`stamp!`] macro embeds a second bitplane with the image's transparency,
+//! and yields a [`MaskedStamp`] instead of a [`Stamp`]. Its pixels are either
+//! [`Black`](MaskedColor::Black), [`White`](MaskedColor::White), or
+//! [`Transparent`](MaskedColor::Transparent), so black pixels no longer have to
+//! double as transparent ones.
+//!
+//! ## Sized stamps
+//!
+//! Passing `sized` to the [`stamp!`] macro yields a [`SizedStamp`], whose width and
+//! height are part of its type, e.g. `static ICON: SizedStamp<16, 16> =
+//! stamp!("icon.png", sized);`, so an image of the wrong size fails to compile. A
+//! sized stamp can be borrowed or converted as a plain [`Stamp`].
+//!
+//! ## Const contexts
+//!
+//! The dimensions of a [`Stamp`], a [`MaskedStamp`], or a [`SizedStamp`] can be
+//! queried in const contexts, e.g. to center a stamp, to size a buffer, or to assert
+//! that an asset fits the screen:
+//!
+//! ```rust
+//! use stockbook::{stamp, Stamp};
+//!
+//! # macro_rules! stamp {
+//! #     ($path:literal) => { unsafe { Stamp::from_raw(30, 10, [0; 38].as_ptr()) } };
+//! # }
+//! static LOGO: Stamp = stamp!("logo.png");
+//!
+//! const SCREEN_WIDTH: usize = 84;
+//! const LOGO_X: usize = (SCREEN_WIDTH - LOGO.width()) / 2;
+//! const _: () = assert!(LOGO.width() <= SCREEN_WIDTH);
+//!
+//! let buffer = [0u8; LOGO.pixel_count().div_ceil(8)];
+//! # assert_eq!(LOGO_X, 27);
+//! # assert_eq!(buffer.len(), 38);
+//! ```
+//!
+//! Without the `progmem` feature, the colors of the pixels can be read in const
+//! contexts as well, with [`Stamp::get_color`] and its relatives. The program memory
+//! can't be read at compile time, so with the `progmem` feature, the dimensions are
+//! all there is.
+//!
+//! ## Limits
+//!
+//! Assets that are too large can be caught at compile time by passing `max_bytes`
+//! or `max_size` to the [`stamp!`] macro, e.g.
+//! `stamp!("boot.png", max_bytes = 1024, max_size = (128, 64))`.
+//!
+//! ## Vector images
+//!
+//! With the `svg` feature, the [`stamp!`] macro rasterizes SVG images as well, e.g.
+//! `stamp!("icon.svg", size = (16, 16))`, and thresholds or dithers the result like
+//! any other image.
+//!
+//! ## Size report
+//!
+//! Setting the `STOCKBOOK_REPORT` environment variable to `json` or `csv` makes the
+//! macros write a report of every stamp that they embed, with the location of the
+//! invocation, the source file, the dimensions, the encoding, and the number of
+//! bytes, e.g. `STOCKBOOK_REPORT=csv cargo build`. Every crate gets its own
+//! `stockbook-report-<crate>.json` or `.csv` file in the directory given by the
+//! `STOCKBOOK_REPORT_DIR` environment variable, or in its `OUT_DIR` if it has a
+//! build script. Stamps of the same invocation, like the tiles of a sprite sheet or
+//! the glyphs of a font, are listed separately, by name, and one that's identical to
+//! a stamp before it takes 0 bytes.
+//!
 //! ## Supported formats
 //!
 //! Stockbook uses the [image](https://docs.rs/image) crate under the hood. See its
 //! own [list of supported formats](https://docs.rs/image/latest/image/codecs/index.html#supported-formats)
 //! for more details.
 //!
+//! On top of that, X11 bitmaps (`.xbm`), as well as plain and raw PBM images
+//! (`.pbm`), are read by Stockbook itself. Both formats are 1-bit already, so their
+//! pixels need no conversion.
+//!
 //! ## Feature flags
 //!
 //! - **`progmem`** &mdash; wraps all pixel data of `Stamp`s in
@@ -78,12 +203,16 @@
 //!   Combined with the `avr` target architecture, this allows you to keep most of
 //!   the data in program memory without the need to copy it to RAM. A no-op for
 //!   non-`avr` target architectures.
+//! - **`svg`** &mdash; enables rasterizing SVG images in the [`stamp!`] macro and
+//!   its relatives.
+//! - **`ttf`** &mdash; enables the `ttf_font!` macro, which rasterizes TrueType and
+//!   OpenType fonts at compile time.
 //!
 //! ## Unstable features
 //!
 //! Although this library works on `stable`, any changes to images referenced by the
 //! [`stamp!`] macro might not be detected because of caching. Therefore, until
-//! [`track_path` API](https://doc.rust-lang.org/stable/proc_macro/tracked_path/fn.path.html)
+//! [`track_path` API](https://doc.rust-lang.org/nightly/proc_macro/tracked/fn.path.html)
 //! ([Tracking Issue](https://github.com/rust-lang/rust/issues/99515)) stabilizes,
 //! it is recommended to use the `nightly` toolchain, however functionality behind
 //! this feature is unstable and may change or stop compiling at any time.
@@ -91,13 +220,48 @@
 #![no_std]
 #![warn(missing_docs)]
 
+/// Defines a function that is `const`, unless the `progmem` feature is enabled, since
+/// reading from the program memory can't be done at compile time.
+macro_rules! const_unless_progmem {
+    ($(#[$attr:meta])* $vis:vis unsafe fn $($rest:tt)*) => {
+        $(#[$attr])*
+        #[cfg(not(feature = "progmem"))]
+        $vis const unsafe fn $($rest)*
+
+        $(#[$attr])*
+        #[cfg(feature = "progmem")]
+        $vis unsafe fn $($rest)*
+    };
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        $(#[$attr])*
+        #[cfg(not(feature = "progmem"))]
+        $vis const fn $($rest)*
+
+        $(#[$attr])*
+        #[cfg(feature = "progmem")]
+        $vis fn $($rest)*
+    };
+}
+
+mod animation;
 mod data;
+mod font;
 mod iter;
+mod masked;
+mod sized;
 
+pub use animation::*;
 use data::*;
+pub use font::*;
 use iter::*;
+pub use masked::*;
+pub use sized::*;
 
-pub use stockbook_stamp_macro::stamp;
+#[cfg(feature = "ttf")]
+pub use stockbook_stamp_macro::ttf_font;
+pub use stockbook_stamp_macro::{
+    animation, bdf_font, font, stamp, stamp_ascii, stamp_enum, stamp_sheet, stamps, text_stamp,
+};
 
 /// Rectangular, 1-bit, raster image.
 ///
@@ -130,7 +294,7 @@ impl Stamp {
     /// assert_eq!(IMAGE.size(), [3, 2]);
     /// ```
     #[inline]
-    pub fn size(&self) -> [usize; 2] {
+    pub const fn size(&self) -> [usize; 2] {
         [self.width, self.height]
     }
 
@@ -149,7 +313,7 @@ impl Stamp {
     /// assert_eq!(IMAGE.width(), 3);
     /// ```
     #[inline]
-    pub fn width(&self) -> usize {
+    pub const fn width(&self) -> usize {
         self.width
     }
 
@@ -168,7 +332,7 @@ impl Stamp {
     /// assert_eq!(IMAGE.height(), 2);
     /// ```
     #[inline]
-    pub fn height(&self) -> usize {
+    pub const fn height(&self) -> usize {
         self.height
     }
 
@@ -187,7 +351,7 @@ impl Stamp {
     /// assert_eq!(IMAGE.pixel_count(), 6);
     /// ```
     #[inline]
-    pub fn pixel_count(&self) -> usize {
+    pub const fn pixel_count(&self) -> usize {
         self.width * self.height
     }
 
@@ -208,7 +372,7 @@ impl Stamp {
     /// assert!(!IMAGE.is_within_bounds(5, 3));
     /// assert!(!IMAGE.is_within_bounds(4, 4));
     /// ```
-    pub fn is_within_bounds(&self, x: usize, y: usize) -> bool {
+    pub const fn is_within_bounds(&self, x: usize, y: usize) -> bool {
         x < self.width && y < self.height
     }
 
@@ -243,94 +407,98 @@ impl Stamp {
         Pixels::new(self)
     }
 
-    /// Yields the color of the stamp at the provided coordinate. Panicking version of
-    /// [`get_color_checked`](Stamp::get_color_checked).
-    ///
-    /// # Panics
-    ///
-    /// This method panics if the coordinate is out of bounds.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// use stockbook::{stamp, Color, Stamp};
-    ///
-    /// # macro_rules! stamp {
-    /// #     ($path:literal) => { unsafe { Stamp::from_raw(3, 3, [0b101_010_10, 0b1_0000000].as_ptr()) } };
-    /// # }
-    /// static IMAGE: Stamp = stamp!("checkerboard_3x3.png");
-    ///
-    /// assert_eq!(IMAGE.get_color(0, 0), Color::White);
-    /// assert_eq!(IMAGE.get_color(1, 0), Color::Black);
-    /// assert_eq!(IMAGE.get_color(0, 1), Color::Black);
-    /// ```
-    pub fn get_color(&self, x: usize, y: usize) -> Color {
-        self.get_color_checked(x, y).expect("")
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate. Panicking version of
+        /// [`get_color_checked`](Stamp::get_color_checked).
+        ///
+        /// # Panics
+        ///
+        /// This method panics if the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, Color, Stamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal) => { unsafe { Stamp::from_raw(3, 3, [0b101_010_10, 0b1_0000000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: Stamp = stamp!("checkerboard_3x3.png");
+        ///
+        /// assert_eq!(IMAGE.get_color(0, 0), Color::White);
+        /// assert_eq!(IMAGE.get_color(1, 0), Color::Black);
+        /// assert_eq!(IMAGE.get_color(0, 1), Color::Black);
+        /// ```
+        pub fn get_color(&self, x: usize, y: usize) -> Color {
+            self.get_color_checked(x, y).expect("")
+        }
     }
 
-    /// Yields the color of the stamp at the provided coordinate. Returns [`None`] if
-    /// the coordinate is out of bounds.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// use stockbook::{stamp, Color, Stamp};
-    ///
-    /// # macro_rules! stamp {
-    /// #     ($path:literal) => { unsafe { Stamp::from_raw(3, 3, [0b101_010_10, 0b1_0000000].as_ptr()) } };
-    /// # }
-    /// static IMAGE: Stamp = stamp!("checkerboard_3x3.png");
-    ///
-    /// assert_eq!(IMAGE.get_color_checked(0, 0), Some(Color::White));
-    /// assert_eq!(IMAGE.get_color_checked(1, 0), Some(Color::Black));
-    /// assert_eq!(IMAGE.get_color_checked(3, 0), None);
-    /// assert_eq!(IMAGE.get_color_checked(0, 3), None);
-    /// ```
-    pub fn get_color_checked(&self, x: usize, y: usize) -> Option<Color> {
-        if !self.is_within_bounds(x, y) {
-            return None;
-        }
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate. Returns [`None`] if
+        /// the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, Color, Stamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal) => { unsafe { Stamp::from_raw(3, 3, [0b101_010_10, 0b1_0000000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: Stamp = stamp!("checkerboard_3x3.png");
+        ///
+        /// assert_eq!(IMAGE.get_color_checked(0, 0), Some(Color::White));
+        /// assert_eq!(IMAGE.get_color_checked(1, 0), Some(Color::Black));
+        /// assert_eq!(IMAGE.get_color_checked(3, 0), None);
+        /// assert_eq!(IMAGE.get_color_checked(0, 3), None);
+        /// ```
+        pub fn get_color_checked(&self, x: usize, y: usize) -> Option<Color> {
+            if !self.is_within_bounds(x, y) {
+                return None;
+            }
 
-        // SAFETY: we just checked the coordinates are within the bounds of the stamp
-        let color = unsafe { self.get_color_unchecked(x, y) };
-        Some(color)
+            // SAFETY: we just checked the coordinates are within the bounds of the stamp
+            let color = unsafe { self.get_color_unchecked(x, y) };
+            Some(color)
+        }
     }
 
-    /// Yields the color of the stamp at the provided coordinate, without doing bounds
-    /// checking.
-    ///
-    /// For a safe alternative see [`get_color`](Stamp::get_color) or
-    /// [`get_color_checked`](Stamp::get_color_checked).
-    ///
-    /// # Safety
-    ///
-    /// Callers must ensure that the provided coordinate is within the bounds of the stamp.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// use stockbook::{stamp, Color, Stamp};
-    ///
-    /// # macro_rules! stamp {
-    /// #     ($path:literal) => { unsafe { Stamp::from_raw(3, 3, [0b101_010_10, 0b1_0000000].as_ptr()) } };
-    /// # }
-    /// static IMAGE: Stamp = stamp!("checkerboard_3x3.png");
-    ///
-    /// // SAFETY: provided coordinates are guaranteed to be within the bounds
-    /// // of the stamp
-    /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 0) }, Color::White);
-    /// assert_eq!(unsafe { IMAGE.get_color_unchecked(1, 0) }, Color::Black);
-    /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 1) }, Color::Black);
-    /// ```
-    pub unsafe fn get_color_unchecked(&self, x: usize, y: usize) -> Color {
-        let idx = y * self.width + x;
-        let byte = self.data.get_unchecked(idx / 8);
-        let mask = 0b10000000 >> (idx % 8);
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate, without doing bounds
+        /// checking.
+        ///
+        /// For a safe alternative see [`get_color`](Stamp::get_color) or
+        /// [`get_color_checked`](Stamp::get_color_checked).
+        ///
+        /// # Safety
+        ///
+        /// Callers must ensure that the provided coordinate is within the bounds of the stamp.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, Color, Stamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal) => { unsafe { Stamp::from_raw(3, 3, [0b101_010_10, 0b1_0000000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: Stamp = stamp!("checkerboard_3x3.png");
+        ///
+        /// // SAFETY: provided coordinates are guaranteed to be within the bounds
+        /// // of the stamp
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 0) }, Color::White);
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(1, 0) }, Color::Black);
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 1) }, Color::Black);
+        /// ```
+        pub unsafe fn get_color_unchecked(&self, x: usize, y: usize) -> Color {
+            let idx = y * self.width + x;
 
-        if byte & mask != 0 {
-            Color::White
-        } else {
-            Color::Black
+            if self.data.get_bit_unchecked(idx) {
+                Color::White
+            } else {
+                Color::Black
+            }
         }
     }
 
@@ -0,0 +1,404 @@
+use crate::{Color, Data, MaskedPixels, Stamp};
+
+/// Rectangular, 1-bit, raster image with a transparency mask.
+///
+/// A masked stamp is a [`Stamp`] paired with a second bitplane, which tells whether
+/// each pixel is opaque or transparent. Masked stamps are produced by the
+/// [`stamp!`](crate::stamp!) macro when it's given the `transparent` argument.
+///
+/// The color of a transparent pixel is [`Transparent`](MaskedColor::Transparent),
+/// while the color of an opaque one is either [`Black`](MaskedColor::Black) or
+/// [`White`](MaskedColor::White).
+#[derive(Debug, Clone)]
+pub struct MaskedStamp {
+    stamp: Stamp,
+    mask: Data,
+}
+
+impl MaskedStamp {
+    /// Size of the stamp in pixels &mdash; width and height, or columns and rows.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 2, [0b000_000_00].as_ptr(), [0b111_111_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("image_3x2.png", transparent = "alpha");
+    ///
+    /// assert_eq!(IMAGE.size(), [3, 2]);
+    /// ```
+    #[inline]
+    pub const fn size(&self) -> [usize; 2] {
+        self.stamp.size()
+    }
+
+    /// Width of the stamp in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 2, [0b000_000_00].as_ptr(), [0b111_111_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("image_3x2.png", transparent = "alpha");
+    ///
+    /// assert_eq!(IMAGE.width(), 3);
+    /// ```
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.stamp.width()
+    }
+
+    /// Height of the stamp in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 2, [0b000_000_00].as_ptr(), [0b111_111_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("image_3x2.png", transparent = "alpha");
+    ///
+    /// assert_eq!(IMAGE.height(), 2);
+    /// ```
+    #[inline]
+    pub const fn height(&self) -> usize {
+        self.stamp.height()
+    }
+
+    /// Number of pixels in the stamp.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 2, [0b000_000_00].as_ptr(), [0b111_111_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("image_3x2.png", transparent = "alpha");
+    ///
+    /// assert_eq!(IMAGE.pixel_count(), 6);
+    /// ```
+    #[inline]
+    pub const fn pixel_count(&self) -> usize {
+        self.stamp.pixel_count()
+    }
+
+    /// Checks if a given coordinate is within the bounds of the image.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 2, [0b000_000_00].as_ptr(), [0b111_111_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("image_3x2.png", transparent = "alpha");
+    ///
+    /// assert!(IMAGE.is_within_bounds(2, 1));
+    /// assert!(!IMAGE.is_within_bounds(3, 1));
+    /// ```
+    pub const fn is_within_bounds(&self, x: usize, y: usize) -> bool {
+        self.stamp.is_within_bounds(x, y)
+    }
+
+    /// The colors of the stamp without the mask. Transparent pixels are
+    /// [`Black`](Color::Black).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, Color, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(2, 1, [0b10_000000].as_ptr(), [0b10_000000].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("white_and_transparent_2x1.png", transparent = "alpha");
+    ///
+    /// assert_eq!(IMAGE.stamp().get_color(0, 0), Color::White);
+    /// assert_eq!(IMAGE.stamp().get_color(1, 0), Color::Black);
+    /// ```
+    #[inline]
+    pub const fn stamp(&self) -> &Stamp {
+        &self.stamp
+    }
+
+    /// Returns an iterator over all pixels of a [`MaskedStamp`]. The iteration order
+    /// is _x_ from 0 to _width_, then _y_ from 0 to _height_. A pixel is a
+    /// _(x, y, color)_ tuple.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, MaskedColor, MaskedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+    /// # }
+    /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+    ///
+    /// let mut pixels = IMAGE.pixels();
+    ///
+    /// assert_eq!(pixels.next(), Some((0, 0, MaskedColor::White)));
+    /// assert_eq!(pixels.next(), Some((1, 0, MaskedColor::Black)));
+    /// assert_eq!(pixels.next(), Some((2, 0, MaskedColor::Transparent)));
+    /// assert_eq!(pixels.next(), None);
+    /// ```
+    pub fn pixels(&self) -> MaskedPixels<'_> {
+        MaskedPixels::new(self)
+    }
+
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate. Panicking version of
+        /// [`get_color_checked`](MaskedStamp::get_color_checked).
+        ///
+        /// # Panics
+        ///
+        /// This method panics if the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, MaskedColor, MaskedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+        ///
+        /// assert_eq!(IMAGE.get_color(0, 0), MaskedColor::White);
+        /// assert_eq!(IMAGE.get_color(1, 0), MaskedColor::Black);
+        /// assert_eq!(IMAGE.get_color(2, 0), MaskedColor::Transparent);
+        /// ```
+        pub fn get_color(&self, x: usize, y: usize) -> MaskedColor {
+            self.get_color_checked(x, y).expect("")
+        }
+    }
+
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate. Returns [`None`] if
+        /// the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, MaskedColor, MaskedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+        ///
+        /// assert_eq!(IMAGE.get_color_checked(0, 0), Some(MaskedColor::White));
+        /// assert_eq!(IMAGE.get_color_checked(2, 0), Some(MaskedColor::Transparent));
+        /// assert_eq!(IMAGE.get_color_checked(3, 0), None);
+        /// ```
+        pub fn get_color_checked(&self, x: usize, y: usize) -> Option<MaskedColor> {
+            if !self.is_within_bounds(x, y) {
+                return None;
+            }
+
+            // SAFETY: we just checked the coordinates are within the bounds of the stamp
+            let color = unsafe { self.get_color_unchecked(x, y) };
+            Some(color)
+        }
+    }
+
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate, without doing bounds
+        /// checking.
+        ///
+        /// For a safe alternative see [`get_color`](MaskedStamp::get_color) or
+        /// [`get_color_checked`](MaskedStamp::get_color_checked).
+        ///
+        /// # Safety
+        ///
+        /// Callers must ensure that the provided coordinate is within the bounds of the stamp.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, MaskedColor, MaskedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+        ///
+        /// // SAFETY: provided coordinates are guaranteed to be within the bounds
+        /// // of the stamp
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 0) }, MaskedColor::White);
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(2, 0) }, MaskedColor::Transparent);
+        /// ```
+        pub unsafe fn get_color_unchecked(&self, x: usize, y: usize) -> MaskedColor {
+            if self.is_opaque_unchecked(x, y) {
+                match self.stamp.get_color_unchecked(x, y) {
+                    Color::Black => MaskedColor::Black,
+                    Color::White => MaskedColor::White,
+                }
+            } else {
+                MaskedColor::Transparent
+            }
+        }
+    }
+
+    const_unless_progmem! {
+        /// Checks if the pixel at the provided coordinate is opaque. Panicking version of
+        /// [`is_opaque_checked`](MaskedStamp::is_opaque_checked).
+        ///
+        /// # Panics
+        ///
+        /// This method panics if the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, MaskedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+        ///
+        /// assert!(IMAGE.is_opaque(1, 0));
+        /// assert!(!IMAGE.is_opaque(2, 0));
+        /// ```
+        pub fn is_opaque(&self, x: usize, y: usize) -> bool {
+            self.is_opaque_checked(x, y).expect("")
+        }
+    }
+
+    const_unless_progmem! {
+        /// Checks if the pixel at the provided coordinate is opaque. Returns [`None`] if
+        /// the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, MaskedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+        ///
+        /// assert_eq!(IMAGE.is_opaque_checked(1, 0), Some(true));
+        /// assert_eq!(IMAGE.is_opaque_checked(2, 0), Some(false));
+        /// assert_eq!(IMAGE.is_opaque_checked(3, 0), None);
+        /// ```
+        pub fn is_opaque_checked(&self, x: usize, y: usize) -> Option<bool> {
+            if !self.is_within_bounds(x, y) {
+                return None;
+            }
+
+            // SAFETY: we just checked the coordinates are within the bounds of the stamp
+            let opaque = unsafe { self.is_opaque_unchecked(x, y) };
+            Some(opaque)
+        }
+    }
+
+    const_unless_progmem! {
+        /// Checks if the pixel at the provided coordinate is opaque, without doing bounds
+        /// checking.
+        ///
+        /// For a safe alternative see [`is_opaque`](MaskedStamp::is_opaque) or
+        /// [`is_opaque_checked`](MaskedStamp::is_opaque_checked).
+        ///
+        /// # Safety
+        ///
+        /// Callers must ensure that the provided coordinate is within the bounds of the stamp.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, MaskedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, transparent = $key:literal) => { unsafe { MaskedStamp::from_raw(3, 1, [0b100_00000].as_ptr(), [0b110_00000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: MaskedStamp = stamp!("image_3x1.png", transparent = "alpha");
+        ///
+        /// // SAFETY: provided coordinates are guaranteed to be within the bounds
+        /// // of the stamp
+        /// assert!(unsafe { IMAGE.is_opaque_unchecked(1, 0) });
+        /// assert!(!unsafe { IMAGE.is_opaque_unchecked(2, 0) });
+        /// ```
+        pub unsafe fn is_opaque_unchecked(&self, x: usize, y: usize) -> bool {
+            let idx = y * self.width() + x;
+
+            self.mask.get_bit_unchecked(idx)
+        }
+    }
+
+    /// Constructs a new masked stamp.
+    ///
+    /// You should not need to call this function directly. It is recommended to use the
+    /// [`stamp!`](crate::stamp!) macro instead, which calls this constructor for you,
+    /// while enforcing its contract.
+    ///
+    /// # Safety
+    ///
+    /// Both `data` and `mask` must fulfill the contract of `data` in
+    /// [`Stamp::from_raw`]. A set bit in `mask` marks the corresponding pixel as
+    /// opaque.
+    pub const unsafe fn from_raw(
+        width: usize,
+        height: usize,
+        data: *const u8,
+        mask: *const u8,
+    ) -> Self {
+        Self {
+            stamp: Stamp::from_raw(width, height, data),
+            mask: Data::from_raw(mask),
+        }
+    }
+}
+
+/// Color of a pixel of a [`MaskedStamp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskedColor {
+    /// Opaque black (`#000000ff` or `rgba(0, 0, 0, 255)`)
+    Black,
+    /// Opaque white (`#ffffffff` or `rgba(255, 255, 255, 255)`)
+    White,
+    /// Transparent
+    Transparent,
+}
+
+impl MaskedColor {
+    /// Color of an opaque pixel, or [`None`] if the pixel is transparent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{Color, MaskedColor};
+    ///
+    /// assert_eq!(MaskedColor::White.color(), Some(Color::White));
+    /// assert_eq!(MaskedColor::Transparent.color(), None);
+    /// ```
+    pub const fn color(self) -> Option<Color> {
+        match self {
+            Self::Black => Some(Color::Black),
+            Self::White => Some(Color::White),
+            Self::Transparent => None,
+        }
+    }
+}
+
+impl From<Color> for MaskedColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => Self::Black,
+            Color::White => Self::White,
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{Color, Pixels, Stamp};
+
+/// Rectangular, 1-bit, raster image, whose dimensions are part of its type.
+///
+/// A sized stamp is a [`Stamp`] that is exactly `W` pixels wide and `H` pixels tall.
+/// Sized stamps are produced by the [`stamp!`](crate::stamp!) macro when it's given
+/// the `sized` argument.
+///
+/// Since the dimensions are known at compile time, a sized stamp of the wrong size is
+/// a type error, e.g. when an icon has to fit a slot of a given size, and the index
+/// math of [`get_color_unchecked`](SizedStamp::get_color_unchecked) is done with
+/// constants.
+///
+/// A sized stamp can be used wherever a [`Stamp`] is expected, either by reference,
+/// through [`stamp`](SizedStamp::stamp), or by value, through [`From`].
+#[derive(Debug, Clone)]
+pub struct SizedStamp<const W: usize, const H: usize> {
+    stamp: Stamp,
+}
+
+impl<const W: usize, const H: usize> SizedStamp<W, H> {
+    /// Size of the stamp in pixels &mdash; width and height, or columns and rows.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, SizedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b000_000_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<3, 2> = stamp!("image_3x2.png", sized);
+    ///
+    /// assert_eq!(IMAGE.size(), [3, 2]);
+    /// ```
+    #[inline]
+    pub const fn size(&self) -> [usize; 2] {
+        [W, H]
+    }
+
+    /// Width of the stamp in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, SizedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b000_000_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<3, 2> = stamp!("image_3x2.png", sized);
+    ///
+    /// assert_eq!(IMAGE.width(), 3);
+    /// ```
+    #[inline]
+    pub const fn width(&self) -> usize {
+        W
+    }
+
+    /// Height of the stamp in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, SizedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b000_000_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<3, 2> = stamp!("image_3x2.png", sized);
+    ///
+    /// assert_eq!(IMAGE.height(), 2);
+    /// ```
+    #[inline]
+    pub const fn height(&self) -> usize {
+        H
+    }
+
+    /// Number of pixels in the stamp.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, SizedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b000_000_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<3, 2> = stamp!("image_3x2.png", sized);
+    ///
+    /// assert_eq!(IMAGE.pixel_count(), 6);
+    /// ```
+    #[inline]
+    pub const fn pixel_count(&self) -> usize {
+        W * H
+    }
+
+    /// Checks if a given coordinate is within the bounds of the image.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, SizedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b000_000_00].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<3, 2> = stamp!("image_3x2.png", sized);
+    ///
+    /// assert!(IMAGE.is_within_bounds(2, 1));
+    /// assert!(!IMAGE.is_within_bounds(3, 1));
+    /// ```
+    pub const fn is_within_bounds(&self, x: usize, y: usize) -> bool {
+        x < W && y < H
+    }
+
+    /// The stamp with its dimensions stored at runtime, like any other [`Stamp`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, Color, SizedStamp, Stamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b10_000000].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<2, 1> = stamp!("white_and_black_2x1.png", sized);
+    ///
+    /// fn first_color(stamp: &Stamp) -> Color {
+    ///     stamp.get_color(0, 0)
+    /// }
+    ///
+    /// assert_eq!(first_color(IMAGE.stamp()), Color::White);
+    /// ```
+    #[inline]
+    pub const fn stamp(&self) -> &Stamp {
+        &self.stamp
+    }
+
+    /// Returns an iterator over all pixels of a [`SizedStamp`]. The iteration order
+    /// is _x_ from 0 to _width_, then _y_ from 0 to _height_. A pixel is a
+    /// _(x, y, color)_ tuple.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockbook::{stamp, Color, SizedStamp};
+    ///
+    /// # macro_rules! stamp {
+    /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b10_000000].as_ptr()) } };
+    /// # }
+    /// static IMAGE: SizedStamp<2, 1> = stamp!("white_and_black_2x1.png", sized);
+    ///
+    /// let mut pixels = IMAGE.pixels();
+    ///
+    /// assert_eq!(pixels.next(), Some((0, 0, Color::White)));
+    /// assert_eq!(pixels.next(), Some((1, 0, Color::Black)));
+    /// assert_eq!(pixels.next(), None);
+    /// ```
+    pub fn pixels(&self) -> Pixels<'_> {
+        self.stamp.pixels()
+    }
+
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate. Panicking version of
+        /// [`get_color_checked`](SizedStamp::get_color_checked).
+        ///
+        /// # Panics
+        ///
+        /// This method panics if the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, Color, SizedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b101_010_10, 0b1_0000000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: SizedStamp<3, 3> = stamp!("checkerboard_3x3.png", sized);
+        ///
+        /// assert_eq!(IMAGE.get_color(0, 0), Color::White);
+        /// assert_eq!(IMAGE.get_color(1, 0), Color::Black);
+        /// assert_eq!(IMAGE.get_color(0, 1), Color::Black);
+        /// ```
+        pub fn get_color(&self, x: usize, y: usize) -> Color {
+            self.get_color_checked(x, y).expect("")
+        }
+    }
+
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate. Returns [`None`] if
+        /// the coordinate is out of bounds.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, Color, SizedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b101_010_10, 0b1_0000000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: SizedStamp<3, 3> = stamp!("checkerboard_3x3.png", sized);
+        ///
+        /// assert_eq!(IMAGE.get_color_checked(0, 0), Some(Color::White));
+        /// assert_eq!(IMAGE.get_color_checked(1, 0), Some(Color::Black));
+        /// assert_eq!(IMAGE.get_color_checked(3, 0), None);
+        /// assert_eq!(IMAGE.get_color_checked(0, 3), None);
+        /// ```
+        pub fn get_color_checked(&self, x: usize, y: usize) -> Option<Color> {
+            if !self.is_within_bounds(x, y) {
+                return None;
+            }
+
+            // SAFETY: we just checked the coordinates are within the bounds of the stamp
+            let color = unsafe { self.get_color_unchecked(x, y) };
+            Some(color)
+        }
+    }
+
+    const_unless_progmem! {
+        /// Yields the color of the stamp at the provided coordinate, without doing bounds
+        /// checking.
+        ///
+        /// For a safe alternative see [`get_color`](SizedStamp::get_color) or
+        /// [`get_color_checked`](SizedStamp::get_color_checked).
+        ///
+        /// # Safety
+        ///
+        /// Callers must ensure that the provided coordinate is within the bounds of the stamp.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use stockbook::{stamp, Color, SizedStamp};
+        ///
+        /// # macro_rules! stamp {
+        /// #     ($path:literal, sized) => { unsafe { SizedStamp::from_raw([0b101_010_10, 0b1_0000000].as_ptr()) } };
+        /// # }
+        /// static IMAGE: SizedStamp<3, 3> = stamp!("checkerboard_3x3.png", sized);
+        ///
+        /// // SAFETY: provided coordinates are guaranteed to be within the bounds
+        /// // of the stamp
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 0) }, Color::White);
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(1, 0) }, Color::Black);
+        /// assert_eq!(unsafe { IMAGE.get_color_unchecked(0, 1) }, Color::Black);
+        /// ```
+        pub unsafe fn get_color_unchecked(&self, x: usize, y: usize) -> Color {
+            let idx = y * W + x;
+
+            if self.stamp.data.get_bit_unchecked(idx) {
+                Color::White
+            } else {
+                Color::Black
+            }
+        }
+    }
+
+    /// Constructs a new sized stamp.
+    ///
+    /// You should not need to call this function directly. It is recommended to use the
+    /// [`stamp!`](crate::stamp!) macro instead, which calls this constructor for you,
+    /// while enforcing its contract.
+    ///
+    /// # Safety
+    ///
+    /// `data` must fulfill the contract of `data` in [`Stamp::from_raw`], for a stamp
+    /// that is `W` pixels wide and `H` pixels tall.
+    pub const unsafe fn from_raw(data: *const u8) -> Self {
+        Self {
+            stamp: Stamp::from_raw(W, H, data),
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> From<SizedStamp<W, H>> for Stamp {
+    fn from(stamp: SizedStamp<W, H>) -> Self {
+        stamp.stamp
+    }
+}
+
+impl<const W: usize, const H: usize> AsRef<Stamp> for SizedStamp<W, H> {
+    fn as_ref(&self) -> &Stamp {
+        &self.stamp
+    }
+}
@@ -1,4 +1,7 @@
-use stockbook::{stamp, Color, Stamp};
+use stockbook::{
+    animation, bdf_font, font, stamp, stamp_ascii, stamp_enum, stamp_sheet, stamps, text_stamp,
+    Animation, Color, Font, MaskedColor, MaskedStamp, SizedStamp, Stamp,
+};
 
 static STAMP: Stamp = stamp!("tests/assets/checkerboard_2x2.png");
 
@@ -38,3 +41,660 @@ fn integration() {
     assert_eq!(pixels.next(), Some((1, 1, Color::White)));
     assert_eq!(pixels.next(), None);
 }
+
+static RELATIVE_TO_FILE: Stamp = stamp!("assets/checkerboard_2x2.png");
+static RELATIVE_TO_MANIFEST: Stamp = stamp!(
+    "tests/assets/checkerboard_2x2.png",
+    relative_to = "manifest",
+);
+
+#[test]
+fn relative_paths() {
+    assert!(RELATIVE_TO_FILE.pixels().eq(STAMP.pixels()));
+    assert!(RELATIVE_TO_MANIFEST.pixels().eq(STAMP.pixels()));
+}
+
+static CHECKERBOARD_TILES: [Stamp; 4] = stamp_sheet!("assets/checkerboard_2x2.png", tile = (1, 1));
+
+#[test]
+fn shared_pixel_data() {
+    // The `Debug` output of a stamp includes the address of its pixel data
+    let [white, black, other_black, other_white] = CHECKERBOARD_TILES
+        .each_ref()
+        .map(|tile| format!("{:?}", tile));
+
+    assert_eq!(white, other_white);
+    assert_eq!(black, other_black);
+    assert_ne!(white, black);
+}
+
+static SIZED: SizedStamp<2, 2> = stamp!("assets/checkerboard_2x2.png", sized);
+
+#[test]
+fn sized() {
+    assert_eq!(SIZED.size(), [2, 2]);
+    assert_eq!(SIZED.pixel_count(), 4);
+    assert!(!SIZED.is_within_bounds(2, 1));
+
+    assert_eq!(SIZED.get_color(0, 0), Color::White);
+    assert_eq!(SIZED.get_color_checked(1, 0), Some(Color::Black));
+    assert_eq!(SIZED.get_color_checked(0, 2), None);
+    assert!(SIZED.pixels().eq(STAMP.pixels()));
+
+    let stamp = Stamp::from(SIZED.clone());
+    assert_eq!(stamp.size(), [2, 2]);
+    assert!(SIZED.stamp().pixels().eq(STAMP.pixels()));
+}
+
+const SCREEN_WIDTH: usize = 84;
+const STAMP_X: usize = (SCREEN_WIDTH - STAMP.width()) / 2;
+const _: () = assert!(SIZED.pixel_count() == STAMP.pixel_count());
+
+static CORNERS: Stamp = stamp_ascii!(
+    "#..
+     ...
+     ..#"
+);
+const TOP_LEFT: Color = CORNERS.get_color(0, 0);
+const CENTER: Option<Color> = CORNERS.get_color_checked(1, 1);
+const OUTSIDE: Option<Color> = CORNERS.get_color_checked(3, 0);
+
+#[test]
+fn const_queries() {
+    let buffer = [0u8; CORNERS.pixel_count().div_ceil(8)];
+
+    assert_eq!(STAMP_X, 41);
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(TOP_LEFT, Color::White);
+    assert_eq!(CENTER, Some(Color::Black));
+    assert_eq!(OUTSIDE, None);
+}
+
+static GRADIENT: Stamp = stamp!("assets/gradient_4x1.png", threshold = 128);
+static RED_CHANNEL: Stamp = stamp!("assets/red_blue_2x1.png", threshold = 128, channel = "red");
+static BLUE_CHANNEL: Stamp = stamp!("assets/red_blue_2x1.png", threshold = 128, channel = "blue");
+
+#[test]
+fn threshold() {
+    use Color::*;
+
+    assert_eq!(colors(&GRADIENT), [Black, Black, White, White]);
+    assert_eq!(colors(&RED_CHANNEL), [White, Black]);
+    assert_eq!(colors(&BLUE_CHANNEL), [Black, White]);
+}
+
+static BAYER: Stamp = stamp!("assets/gray_4x4.png", dither = "bayer2x2");
+static FLOYD_STEINBERG: Stamp = stamp!("assets/gray_4x4.png", dither = "floyd-steinberg");
+static ATKINSON: Stamp = stamp!("assets/gray_4x4.png", dither = "atkinson");
+
+#[test]
+fn dither() {
+    use Color::*;
+
+    #[rustfmt::skip]
+    let checkerboard = [
+        White, Black, White, Black,
+        Black, White, Black, White,
+        White, Black, White, Black,
+        Black, White, Black, White,
+    ];
+
+    assert_eq!(colors(&BAYER), checkerboard);
+    assert_eq!(colors(&FLOYD_STEINBERG), checkerboard);
+    assert_eq!(colors(&ATKINSON).iter().filter(|&&c| c == White).count(), 8);
+}
+
+static ALPHA: MaskedStamp = stamp!("assets/alpha_2x2.png", transparent = "alpha");
+static COLOR_KEY: MaskedStamp = stamp!("assets/color_key_3x1.png", transparent = "#ff00ff");
+
+#[test]
+fn transparency() {
+    use MaskedColor::*;
+
+    assert_eq!(ALPHA.size(), [2, 2]);
+    assert_eq!(ALPHA.get_color(0, 0), White);
+    assert_eq!(ALPHA.get_color(1, 0), Transparent);
+    assert_eq!(ALPHA.get_color(0, 1), Black);
+    assert_eq!(ALPHA.get_color(1, 1), Transparent);
+
+    assert_eq!(ALPHA.is_opaque_checked(0, 1), Some(true));
+    assert_eq!(ALPHA.is_opaque_checked(1, 1), Some(false));
+    assert_eq!(ALPHA.is_opaque_checked(2, 1), None);
+
+    let pixels = COLOR_KEY.pixels().collect::<Vec<_>>();
+    assert_eq!(pixels, [(0, 0, Transparent), (1, 0, White), (2, 0, Black)]);
+    assert_eq!(
+        colors(COLOR_KEY.stamp()),
+        [Color::Black, Color::White, Color::Black]
+    );
+}
+
+static PALETTE: Stamp = stamp!(
+    "assets/game_boy_2x1.png",
+    black = "#0f380f",
+    white = "#9bbc0f",
+);
+static INVERTED_PALETTE: Stamp = stamp!(
+    "assets/game_boy_2x1.png",
+    black = "#0f380f",
+    white = "#9bbc0f",
+    invert,
+);
+static INVERTED: Stamp = stamp!("assets/checkerboard_2x2.png", invert);
+static INVERTED_THRESHOLD: Stamp = stamp!("assets/gradient_4x1.png", threshold = 128, invert);
+
+#[test]
+fn palette() {
+    use Color::*;
+
+    assert_eq!(colors(&PALETTE), [Black, White]);
+    assert_eq!(colors(&INVERTED_PALETTE), [White, Black]);
+    assert_eq!(colors(&INVERTED), [Black, White, White, Black]);
+    assert_eq!(colors(&INVERTED_THRESHOLD), [White, White, Black, Black]);
+}
+
+static REGION: Stamp = stamp!("assets/mockup_4x4.png", region = (1, 1, 2, 2));
+
+#[test]
+fn region() {
+    assert_eq!(REGION.size(), [2, 2]);
+    assert_eq!(colors(&REGION), colors(&STAMP));
+}
+
+static ROTATE_90: Stamp = stamp!("assets/arrow_3x2.png", rotate = 90);
+static ROTATE_180: Stamp = stamp!("assets/arrow_3x2.png", rotate = 180);
+static FLIP_X: Stamp = stamp!("assets/arrow_3x2.png", flip_x);
+static FLIP_Y: Stamp = stamp!("assets/arrow_3x2.png", flip_y);
+static ROTATE_90_FLIP_X: Stamp = stamp!("assets/arrow_3x2.png", rotate = 90, flip_x);
+
+#[test]
+fn rotate_and_flip() {
+    use Color::*;
+
+    assert_eq!(ROTATE_90.size(), [2, 3]);
+    assert_eq!(
+        colors(&ROTATE_90),
+        [Black, White, Black, Black, White, White]
+    );
+    assert_eq!(ROTATE_180.size(), [3, 2]);
+    assert_eq!(
+        colors(&ROTATE_180),
+        [White, Black, Black, White, Black, White]
+    );
+    assert_eq!(colors(&FLIP_X), [White, Black, White, White, Black, Black]);
+    assert_eq!(colors(&FLIP_Y), [Black, Black, White, White, Black, White]);
+    assert_eq!(ROTATE_90_FLIP_X.size(), [2, 3]);
+    assert_eq!(
+        colors(&ROTATE_90_FLIP_X),
+        [White, Black, Black, Black, White, White]
+    );
+}
+
+static SCALE_NEAREST: Stamp = stamp!("assets/checkerboard_2x2.png", scale = 2);
+static SCALE_EPX: Stamp = stamp!("assets/arrow_3x2.png", scale = 4, scaler = "epx");
+static SCALE_3X: Stamp = stamp!("assets/arrow_3x2.png", scale = 3, scaler = "scale3x");
+static RESIZE: Stamp = stamp!("assets/gradient_4x1.png", size = (2, 1));
+static EPX_CHECKERBOARD: Stamp = stamp!("assets/checkerboard_2x2.png", scale = 2, scaler = "epx");
+static SCALE_3X_CHECKERBOARD: Stamp =
+    stamp!("assets/checkerboard_2x2.png", scale = 3, scaler = "scale3x");
+
+#[test]
+fn scale() {
+    use Color::*;
+
+    assert_eq!(SCALE_NEAREST.size(), [4, 4]);
+    #[rustfmt::skip]
+    assert_eq!(colors(&SCALE_NEAREST), [
+        White, White, Black, Black,
+        White, White, Black, Black,
+        Black, Black, White, White,
+        Black, Black, White, White,
+    ]);
+
+    assert_eq!(SCALE_EPX.size(), [12, 8]);
+    assert_eq!(SCALE_3X.size(), [9, 6]);
+
+    // Diagonal neighbours of the same color fill in the corners between them
+    assert_eq!(EPX_CHECKERBOARD.size(), [4, 4]);
+    #[rustfmt::skip]
+    assert_eq!(colors(&EPX_CHECKERBOARD), [
+        White, White, Black, Black,
+        White, Black, White, Black,
+        Black, White, Black, White,
+        Black, Black, White, White,
+    ]);
+
+    assert_eq!(SCALE_3X_CHECKERBOARD.size(), [6, 6]);
+    #[rustfmt::skip]
+    assert_eq!(colors(&SCALE_3X_CHECKERBOARD), [
+        White, White, White, Black, Black, Black,
+        White, White, Black, White, Black, Black,
+        White, Black, Black, White, White, Black,
+        Black, White, White, Black, Black, White,
+        Black, Black, White, Black, White, White,
+        Black, Black, Black, White, White, White,
+    ]);
+
+    assert_eq!(RESIZE.size(), [2, 1]);
+    assert_eq!(colors(&RESIZE), [Black, White]);
+}
+
+static SHEET: [Stamp; 4] = stamp_sheet!(
+    "assets/sheet_7x4.png",
+    tile = (2, 1),
+    margin = 1,
+    spacing = 1,
+);
+static SHEET_PART: [Stamp; 3] = stamp_sheet!(
+    "assets/sheet_7x4.png",
+    tile = (2, 1),
+    margin = 1,
+    spacing = 1,
+    count = 3,
+    invert,
+);
+
+#[test]
+fn sheet() {
+    use Color::*;
+
+    assert!(SHEET.iter().all(|tile| tile.size() == [2, 1]));
+    assert_eq!(colors(&SHEET[0]), [White, Black]);
+    assert_eq!(colors(&SHEET[1]), [Black, White]);
+    assert_eq!(colors(&SHEET[2]), [White, White]);
+    assert_eq!(colors(&SHEET[3]), [Black, Black]);
+
+    assert_eq!(colors(&SHEET_PART[0]), [Black, White]);
+    assert_eq!(colors(&SHEET_PART[2]), [Black, Black]);
+}
+
+static GIF: Animation = animation!("assets/blink_2x1.gif");
+static APNG: Animation = animation!("assets/blink_2x1.png");
+static MASKED_GIF: Animation<MaskedStamp> =
+    animation!("assets/blink_2x1.gif", transparent = "#ffffff");
+
+#[test]
+fn animation() {
+    use Color::*;
+
+    for animation in [&GIF, &APNG] {
+        assert_eq!(animation.frame_count(), 3);
+        assert_eq!(animation.delays(), [100, 200, 50]);
+        assert_eq!(animation.duration(), 350);
+
+        assert_eq!(colors(animation.frame(0)), [White, Black]);
+        assert_eq!(colors(animation.frame(1)), [Black, White]);
+        assert_eq!(colors(animation.frame(2)), [White, White]);
+
+        assert_eq!(animation.frame_index_at(0), 0);
+        assert_eq!(animation.frame_index_at(100), 1);
+        assert_eq!(animation.frame_index_at(300), 2);
+        assert_eq!(animation.frame_index_at(350), 0);
+    }
+
+    assert_eq!(MASKED_GIF.frame_at(150).get_color(0, 0), MaskedColor::Black);
+    assert_eq!(
+        MASKED_GIF.frame_at(150).get_color(1, 0),
+        MaskedColor::Transparent
+    );
+}
+
+stamps!(mod icons, "assets/icons/*.png");
+stamps!(mod masked_icons, "assets/icons/d*.png", transparent = "#000000");
+
+#[test]
+fn directory() {
+    use Color::*;
+
+    assert_eq!(colors(&icons::DOT), [White]);
+    assert_eq!(colors(&icons::DASH), [White, Black]);
+
+    let _: &MaskedStamp = &masked_icons::DOT;
+    assert_eq!(masked_icons::DASH.get_color(1, 0), MaskedColor::Transparent);
+}
+
+stamp_enum! {
+    /// Icons used by the tests.
+    enum Icon {
+        Dot = "assets/icons/dot.png",
+        /// Inverted dash.
+        Dash = ("assets/icons/dash.png", invert),
+    }
+}
+
+stamp_enum! {
+    enum MaskedIcon {
+        Dot = ("assets/icons/dot.png", transparent = "#000000"),
+        Dash = ("assets/icons/dash.png", transparent = "#000000"),
+    }
+}
+
+#[test]
+fn enumeration() {
+    use Color::*;
+
+    assert_eq!(Icon::ALL, [Icon::Dot, Icon::Dash]);
+    assert_eq!(colors(Icon::Dot.stamp()), [White]);
+    assert_eq!(colors(Icon::Dash.stamp()), [Black, White]);
+    assert_eq!(Icon::Dash.name(), "Dash");
+    assert_eq!(Icon::Dash.to_string(), "Dash");
+
+    assert_eq!(MaskedIcon::Dot.stamp().get_color(0, 0), MaskedColor::White);
+    assert_eq!(
+        MaskedIcon::Dash.stamp().get_color(1, 0),
+        MaskedColor::Transparent
+    );
+}
+
+static FONT: Font = font!(
+    "assets/font_3x3.png",
+    glyph = (3, 3),
+    chars = [' ', 'A'..='B', "é"],
+    fallback = 'A',
+    letter_spacing = 1,
+    line_spacing = 2,
+);
+static PROPORTIONAL_FONT: Font = font!(
+    "assets/font_3x3.png",
+    glyph = (3, 3),
+    chars = [' ', 'A'..='B', "é"],
+    proportional,
+);
+
+#[test]
+fn font() {
+    let ink = |font: &Font, text| {
+        font.text(text)
+            .filter(|&(_, _, color)| color == Color::White)
+            .map(|(x, y, _)| (x, y))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(FONT.line_height(), 5);
+    assert_eq!(
+        FONT.stamp('B').map(colors),
+        Some(vec![
+            Color::Black,
+            Color::White,
+            Color::Black,
+            Color::Black,
+            Color::White,
+            Color::Black,
+            Color::Black,
+            Color::White,
+            Color::Black
+        ])
+    );
+    assert_eq!(FONT.glyph('é').map(|glyph| glyph.advance()), Some(4));
+    assert_eq!(
+        FONT.glyph('?').map(|glyph| glyph.stamp().size()),
+        Some([3, 3])
+    );
+    assert_eq!(FONT.text_size("AB\n é"), [8, 10]);
+    assert_eq!(
+        ink(&FONT, "Bé\nB"),
+        [
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (4, 0),
+            (6, 0),
+            (1, 5),
+            (1, 6),
+            (1, 7)
+        ]
+    );
+
+    assert!(PROPORTIONAL_FONT.glyph('?').is_none());
+    assert_eq!(
+        PROPORTIONAL_FONT.glyph(' ').map(|glyph| glyph.advance()),
+        Some(3)
+    );
+    assert_eq!(
+        PROPORTIONAL_FONT.glyph('B').map(|glyph| glyph.advance()),
+        Some(1)
+    );
+    assert_eq!(PROPORTIONAL_FONT.text_size("AB é"), [10, 3]);
+    assert_eq!(
+        ink(&PROPORTIONAL_FONT, "BéB"),
+        [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (3, 0),
+            (4, 0),
+            (4, 1),
+            (4, 2)
+        ]
+    );
+}
+
+static BDF: Font = bdf_font!("assets/tiny.bdf");
+static BDF_SUBSET: Font = bdf_font!("assets/tiny.bdf", chars = "g", fallback = ' ');
+
+#[test]
+fn bdf_font() {
+    let ink = BDF
+        .text("Ag")
+        .filter(|&(_, _, color)| color == Color::White)
+        .map(|(x, y, _)| (x, y))
+        .collect::<Vec<_>>();
+
+    assert_eq!([BDF.ascent(), BDF.descent(), BDF.line_height()], [3, 1, 4]);
+    assert_eq!(BDF.glyph('g').map(|glyph| glyph.offset()), Some([0, 1]));
+    assert_eq!(BDF.text_size("Ag g"), [14, 4]);
+    assert_eq!(
+        ink,
+        [
+            (1, 0),
+            (0, 1),
+            (2, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (4, 1),
+            (5, 1),
+            (5, 2),
+            (4, 3),
+            (5, 3)
+        ]
+    );
+
+    assert_eq!(BDF_SUBSET.stamp('A').map(Stamp::size), Some([0, 0]));
+    assert_eq!(BDF_SUBSET.stamp('g').map(Stamp::size), Some([2, 3]));
+}
+
+static BDF_TEXT: Stamp = text_stamp!("Ag", font = "assets/tiny.bdf");
+static RIGHT_ALIGNED_TEXT: Stamp = text_stamp!(
+    "AB\né",
+    font = "assets/font_3x3.png",
+    glyph = (3, 3),
+    chars = [' ', 'A'..='B', "é"],
+    letter_spacing = 1,
+    align = "right",
+);
+static WRAPPED_TEXT: Stamp = text_stamp!(
+    "A B A",
+    font = "assets/font_3x3.png",
+    glyph = (3, 3),
+    chars = [' ', 'A'..='B', "é"],
+    letter_spacing = 1,
+    max_width = 12,
+);
+
+#[test]
+fn text_stamp() {
+    let ink = |stamp: &Stamp| {
+        stamp
+            .pixels()
+            .filter(|&(_, _, color)| color == Color::White)
+            .map(|(x, y, _)| (x, y))
+            .collect::<Vec<_>>()
+    };
+
+    let mut bdf_ink = BDF
+        .text("Ag")
+        .filter(|&(_, _, color)| color == Color::White)
+        .map(|(x, y, _)| (x, y))
+        .collect::<Vec<_>>();
+    bdf_ink.sort_by_key(|&(x, y)| (y, x));
+
+    assert_eq!(BDF_TEXT.size(), BDF.text_size("Ag"));
+    assert_eq!(ink(&BDF_TEXT), bdf_ink);
+
+    assert_eq!(RIGHT_ALIGNED_TEXT.size(), [8, 6]);
+    assert_eq!(
+        ink(&RIGHT_ALIGNED_TEXT),
+        [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (5, 0),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (5, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (5, 2),
+            (4, 3),
+            (6, 3)
+        ]
+    );
+
+    assert_eq!(WRAPPED_TEXT.size(), [12, 6]);
+    assert_eq!(ink(&WRAPPED_TEXT).len(), 9 + 3 + 9);
+}
+
+#[cfg(feature = "ttf")]
+static TTF: Font = stockbook::ttf_font!("assets/blocks.ttf", size = 10);
+
+#[cfg(feature = "ttf")]
+#[test]
+fn ttf_font() {
+    let a = TTF.glyph('A').unwrap();
+    let g = TTF.glyph('g').unwrap();
+
+    assert_eq!([TTF.ascent(), TTF.descent(), TTF.line_height()], [8, 2, 10]);
+    assert_eq!(
+        [
+            a.stamp().size(),
+            g.stamp().size(),
+            TTF.stamp(' ').unwrap().size()
+        ],
+        [[5, 7], [3, 5], [0, 0]]
+    );
+    assert_eq!([a.offset(), g.offset()], [[0, 1], [1, 5]]);
+    assert_eq!(
+        [a.advance(), g.advance(), TTF.glyph(' ').unwrap().advance()],
+        [6, 5, 3]
+    );
+    assert!(colors(a.stamp()).iter().all(|&color| color == Color::White));
+}
+
+#[cfg(feature = "svg")]
+static SVG: Stamp = stamp!("assets/shapes.svg");
+#[cfg(feature = "svg")]
+static RESIZED_SVG: Stamp = stamp!("assets/shapes.svg", size = (2, 2));
+#[cfg(feature = "svg")]
+static TRANSPARENT_SVG: MaskedStamp = stamp!("assets/shapes.svg", transparent = "alpha");
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg() {
+    use Color::*;
+
+    assert_eq!(SVG.size(), [4, 4]);
+    assert_eq!(
+        colors(&SVG),
+        [
+            Black, Black, White, White, //
+            Black, Black, White, White, //
+            Black, Black, White, White, //
+            Black, Black, White, White,
+        ]
+    );
+    assert_eq!(colors(&RESIZED_SVG), [Black, White, Black, White]);
+
+    assert_eq!(TRANSPARENT_SVG.get_color(1, 3), MaskedColor::Black);
+    assert_eq!(TRANSPARENT_SVG.get_color(3, 0), MaskedColor::White);
+    assert_eq!(TRANSPARENT_SVG.get_color(3, 3), MaskedColor::Transparent);
+}
+
+static XBM: Stamp = stamp!("assets/pattern_10x2.xbm");
+static X10_XBM: Stamp = stamp!("assets/pattern_10x2_x10.xbm");
+static SHORTCUT_XBM: Stamp = stamp!("assets/shortcut_10x2.xbm");
+static PLAIN_PBM: Stamp = stamp!("assets/pattern_10x2_plain.pbm");
+static RAW_PBM: Stamp = stamp!("assets/pattern_10x2_raw.pbm");
+
+#[test]
+fn one_bit_formats() {
+    use Color::*;
+
+    assert_eq!(XBM.size(), [10, 2]);
+    assert_eq!(
+        colors(&XBM),
+        [
+            Black, White, Black, Black, White, White, White, White, White, Black, //
+            White, Black, White, White, Black, Black, Black, Black, Black, White,
+        ]
+    );
+    assert!(X10_XBM.pixels().eq(XBM.pixels()));
+    assert!(SHORTCUT_XBM.pixels().eq(XBM.pixels()));
+    assert!(PLAIN_PBM.pixels().eq(XBM.pixels()));
+    assert!(RAW_PBM.pixels().eq(XBM.pixels()));
+}
+
+static ASCII: Stamp = stamp_ascii!(
+    "
+    #.#.
+    .#.#
+    "
+);
+static ASCII_RAW: Stamp = stamp_ascii!(
+    r"#.#.
+.#.#"
+);
+static ASCII_FILE: Stamp = stamp_ascii!("assets/arrow_3x3.txt");
+
+#[test]
+fn ascii() {
+    use Color::*;
+
+    assert_eq!(ASCII.size(), [4, 2]);
+    assert_eq!(
+        colors(&ASCII),
+        [
+            White, Black, White, Black, //
+            Black, White, Black, White,
+        ]
+    );
+    assert!(ASCII_RAW.pixels().eq(ASCII.pixels()));
+
+    assert_eq!(ASCII_FILE.size(), [3, 3]);
+    assert_eq!(
+        colors(&ASCII_FILE),
+        [
+            White, Black, Black, //
+            White, White, Black, //
+            White, Black, Black,
+        ]
+    );
+}
+
+static WITHIN_LIMITS: Stamp = stamp!(
+    "assets/checkerboard_2x2.png",
+    max_bytes = 1,
+    max_size = (2, 2),
+);
+
+#[test]
+fn limits() {
+    assert!(WITHIN_LIMITS.pixels().eq(STAMP.pixels()));
+}
+
+fn colors(stamp: &Stamp) -> Vec<Color> {
+    stamp.pixels().map(|(_, _, color)| color).collect()
+}